@@ -1,5 +1,5 @@
 use criterion::Criterion;
-use day_01::{read_data, Input, PART_1, PART_2};
+use day_01::{read_data, Input, StreamingInput, PART_1, PART_2};
 
 fn main() {
     bench();
@@ -28,6 +28,8 @@ fn bench() {
         b.iter(|| assert_eq!(PART_2, i.part_2()))
     });
 
+    group.bench_with_input("top 100", &input, |b, i| b.iter(|| i.top_n(100)));
+
     group.bench_with_input("total", &contents, |b, i| {
         b.iter(|| {
             let data = Input::from_data(i);
@@ -36,5 +38,13 @@ fn bench() {
         })
     });
 
+    group.bench_with_input("streaming total", &contents, |b, i| {
+        b.iter(|| {
+            let data = StreamingInput::from_reader(i.as_bytes()).unwrap();
+            assert_eq!(PART_1, data.part_1());
+            assert_eq!(PART_2, data.part_2());
+        })
+    });
+
     group.finish()
 }
@@ -1,9 +1,27 @@
+use clap::Parser;
 use day_01::{read_data, Input};
 
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        help = "Print a ranked table of the top N elves, including ties"
+    )]
+    top: Option<usize>,
+}
+
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
     let input = Input::from_data(&contents);
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
+
+    if let Some(n) = opts.top {
+        println!("\n{}", input.ranking(n));
+    }
 }
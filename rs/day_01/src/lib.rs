@@ -1,4 +1,10 @@
-use std::{fs::read_to_string, path::Path};
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
 
 pub const PART_1: usize = 64929;
 pub const PART_2: usize = 193697;
@@ -10,6 +16,10 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
+pub fn open_data(data_dir: &str) -> BufReader<File> {
+    BufReader::new(File::open(Path::new(data_dir).join("day_01.txt")).unwrap())
+}
+
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Input {
     calorie_counts: Vec<usize>,
@@ -30,18 +40,156 @@ impl Input {
         }
         calorie_counts.push(count);
 
-        calorie_counts.sort_unstable();
-        calorie_counts.reverse();
-
         Self { calorie_counts }
     }
 
     pub fn part_1(&self) -> usize {
-        self.calorie_counts.first().copied().unwrap()
+        self.top_n(1).first().unwrap().calories
+    }
+
+    pub fn part_2(&self) -> usize {
+        self.top_n(3).iter().map(|elf| elf.calories).sum()
+    }
+
+    pub fn elves(&self) -> impl Iterator<Item = Elf> + '_ {
+        self.calorie_counts
+            .iter()
+            .enumerate()
+            .map(|(index, &calories)| Elf { index, calories })
+    }
+
+    pub fn top_n(&self, n: usize) -> Vec<Elf> {
+        let mut elves = self.select_top(n);
+        elves.truncate(n);
+        elves
+    }
+
+    pub fn top_n_with_ties(&self, n: usize) -> Vec<Elf> {
+        self.select_top(n)
+    }
+
+    pub fn ranking(&self, n: usize) -> Ranking {
+        let mut ranks = Vec::new();
+        for (position, elf) in self.top_n_with_ties(n).into_iter().enumerate() {
+            let rank = match ranks.last() {
+                Some(&(rank, Elf { calories, .. })) if calories == elf.calories => rank,
+                _ => position + 1,
+            };
+            ranks.push((rank, elf));
+        }
+        Ranking(ranks)
+    }
+
+    // Returns the top `n` elves in rank order, followed by any others tied
+    // with the `n`th, using a partial selection rather than a full sort.
+    fn select_top(&self, n: usize) -> Vec<Elf> {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut elves = self.elves().collect::<Vec<Elf>>();
+        if n >= elves.len() {
+            elves.sort_unstable_by(Elf::rank_cmp);
+            return elves;
+        }
+
+        elves.select_nth_unstable_by(n - 1, Elf::rank_cmp);
+        let cutoff = elves[n - 1].calories;
+
+        let (top, rest) = elves.split_at_mut(n);
+        top.sort_unstable_by(Elf::rank_cmp);
+
+        let mut tied = 0;
+        for idx in 0..rest.len() {
+            if rest[idx].calories == cutoff {
+                rest.swap(tied, idx);
+                tied += 1;
+            }
+        }
+        rest[..tied].sort_unstable_by(Elf::rank_cmp);
+
+        elves.truncate(n + tied);
+        elves
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elf {
+    pub index: usize,
+    pub calories: usize,
+}
+
+impl Elf {
+    fn rank_cmp(&self, other: &Self) -> Ordering {
+        other
+            .calories
+            .cmp(&self.calories)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranking(pub Vec<(usize, Elf)>);
+
+impl Display for Ranking {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:>4}  {:>5}  {:>8}", "Rank", "Elf", "Calories")?;
+        for (rank, elf) in &self.0 {
+            writeln!(f, "{:>4}  {:>5}  {:>8}", rank, elf.index + 1, elf.calories)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StreamingInput {
+    top_three: [usize; 3],
+}
+
+impl StreamingInput {
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let mut output = Self::default();
+
+        let mut line = String::new();
+        let mut count = None;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+
+            let line = line.trim_end();
+            if line.is_empty() {
+                if let Some(count) = count.take() {
+                    output.push(count);
+                }
+                continue;
+            }
+            *count.get_or_insert(0) += line
+                .parse::<usize>()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        }
+        if let Some(count) = count {
+            output.push(count);
+        }
+
+        Ok(output)
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.top_three[0]
     }
 
     pub fn part_2(&self) -> usize {
-        self.calorie_counts.iter().take(3).sum()
+        self.top_three.iter().sum()
+    }
+
+    fn push(&mut self, count: usize) {
+        // Keep the three largest counts in descending order.
+        if let Some(idx) = self.top_three.iter().position(|&top| count > top) {
+            self.top_three[idx..].rotate_right(1);
+            self.top_three[idx] = count;
+        }
     }
 }
 
@@ -72,6 +220,48 @@ mod tests {
         }
     }
 
+    mod from_reader {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            expected: [usize; 2],
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                input: super::example().0,
+                expected: [24000, 45000],
+            })
+        }
+
+        #[test]
+        fn trailing_blank_lines() {
+            run(&Case {
+                input: "1000\n2000\n\n3000\n\n\n",
+                expected: [3000, 6000],
+            })
+        }
+
+        #[test]
+        fn invalid_line() {
+            let err = StreamingInput::from_reader("1000\nabc\n".as_bytes()).unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidData, err.kind())
+        }
+
+        #[test]
+        fn actual() {
+            let streamed = StreamingInput::from_reader(open_data(DATA_DIR)).unwrap();
+            assert_eq!([PART_1, PART_2], [streamed.part_1(), streamed.part_2()])
+        }
+
+        fn run(test: &Case) {
+            let streamed = StreamingInput::from_reader(test.input.as_bytes()).unwrap();
+            assert_eq!(test.expected, [streamed.part_1(), streamed.part_2()])
+        }
+    }
+
     mod part_1 {
         use super::*;
 
@@ -130,6 +320,194 @@ mod tests {
         }
     }
 
+    mod top_n {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            n: usize,
+            expected: Vec<Elf>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                n: 3,
+                expected: vec![
+                    Elf {
+                        index: 3,
+                        calories: 24000,
+                    },
+                    Elf {
+                        index: 2,
+                        calories: 11000,
+                    },
+                    Elf {
+                        index: 4,
+                        calories: 10000,
+                    },
+                ],
+            })
+        }
+
+        #[test]
+        fn ties_broken_by_index() {
+            run(&Case {
+                data: super::ties(),
+                n: 2,
+                expected: vec![
+                    Elf {
+                        index: 0,
+                        calories: 300,
+                    },
+                    Elf {
+                        index: 2,
+                        calories: 200,
+                    },
+                ],
+            })
+        }
+
+        #[test]
+        fn more_than_elves() {
+            run(&Case {
+                data: super::example().1,
+                n: 10,
+                expected: vec![
+                    Elf {
+                        index: 3,
+                        calories: 24000,
+                    },
+                    Elf {
+                        index: 2,
+                        calories: 11000,
+                    },
+                    Elf {
+                        index: 4,
+                        calories: 10000,
+                    },
+                    Elf {
+                        index: 0,
+                        calories: 6000,
+                    },
+                    Elf {
+                        index: 1,
+                        calories: 4000,
+                    },
+                ],
+            })
+        }
+
+        #[test]
+        fn zero() {
+            run(&Case {
+                data: super::example().1,
+                n: 0,
+                expected: vec![],
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.data.top_n(test.n))
+        }
+    }
+
+    mod top_n_with_ties {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            n: usize,
+            expected: Vec<usize>,
+        }
+
+        #[test]
+        fn no_ties() {
+            run(&Case {
+                data: super::example().1,
+                n: 2,
+                expected: vec![3, 2],
+            })
+        }
+
+        #[test]
+        fn ties() {
+            run(&Case {
+                data: super::ties(),
+                n: 2,
+                expected: vec![0, 2, 3, 4, 5],
+            })
+        }
+
+        #[test]
+        fn zero() {
+            run(&Case {
+                data: super::ties(),
+                n: 0,
+                expected: vec![],
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                test.data
+                    .top_n_with_ties(test.n)
+                    .iter()
+                    .map(|elf| elf.index)
+                    .collect::<Vec<usize>>()
+            )
+        }
+    }
+
+    mod ranking {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            n: usize,
+            expected: &'static str,
+        }
+
+        #[test]
+        fn ties() {
+            run(&Case {
+                data: super::ties(),
+                n: 3,
+                expected: "Rank    Elf  Calories
+   1      1       300
+   2      3       200
+   2      4       200
+   2      5       200
+   2      6       200
+",
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.data.ranking(test.n).to_string())
+        }
+    }
+
+    fn ties() -> Input {
+        Input::from_data(
+            "300
+
+100
+
+200
+
+100
+100
+
+150
+50
+
+200",
+        )
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "1000
@@ -147,7 +525,7 @@ mod tests {
 
 10000",
             Input {
-                calorie_counts: vec![24000, 11000, 10000, 6000, 4000],
+                calorie_counts: vec![6000, 4000, 11000, 24000, 10000],
             },
         )
     }
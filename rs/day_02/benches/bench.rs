@@ -1,5 +1,5 @@
 use criterion::Criterion;
-use day_02::{read_data, Input, PART_1, PART_2};
+use day_02::{read_data, Input, StreamingInput, PART_1, PART_2};
 
 fn main() {
     bench();
@@ -36,5 +36,13 @@ fn bench() {
         })
     });
 
+    group.bench_with_input("streaming total", &contents, |b, i| {
+        b.iter(|| {
+            let data = StreamingInput::from_reader(i.as_bytes()).unwrap();
+            assert_eq!(PART_1, data.part_1());
+            assert_eq!(PART_2, data.part_2());
+        })
+    });
+
     group.finish()
 }
@@ -1,4 +1,9 @@
-use std::{cmp::Ordering, fs::read_to_string, path::Path};
+use std::{
+    fmt::Display,
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
 
 pub const PART_1: usize = 15572;
 pub const PART_2: usize = 16098;
@@ -10,114 +15,334 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
+pub fn open_data(data_dir: &str) -> BufReader<File> {
+    BufReader::new(File::open(Path::new(data_dir).join("day_02.txt")).unwrap())
+}
+
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Input {
-    strategies: Vec<[Shape; 2]>,
+    strategies: Vec<[char; 2]>,
 }
 
 impl Input {
     pub fn from_data(data: &str) -> Self {
-        let mut strategies = Vec::new();
-        for line in data.lines() {
-            let parts = line
-                .split_whitespace()
-                .map(Shape::from)
-                .collect::<Vec<Shape>>();
-            assert_eq!(parts.len(), 2);
-            strategies.push([parts[0], parts[1]]);
-        }
+        let strategies = data
+            .lines()
+            .map(|line| parse_round(line).unwrap())
+            .collect();
         Self { strategies }
     }
 
     pub fn part_1(&self) -> usize {
-        self.strategies.iter().fold(0, |acc, &[opponent, me]| {
-            acc + (me as usize)
-                + match me.cmp(&opponent) {
-                    Ordering::Less => Outcome::Lose,
-                    Ordering::Equal => Outcome::Draw,
-                    Ordering::Greater => Outcome::Win,
-                } as usize
-        })
+        self.total_score(&Strategy::part_1()).unwrap()
     }
 
     pub fn part_2(&self) -> usize {
-        self.strategies.iter().fold(0, |acc, &[opponent, target]| {
-            acc + match target {
-                Shape::Rock => {
-                    Outcome::Lose as usize
-                        + match opponent {
-                            Shape::Rock => Shape::Scissors,
-                            Shape::Paper => Shape::Rock,
-                            Shape::Scissors => Shape::Paper,
-                        } as usize
-                }
-                Shape::Paper => Outcome::Draw as usize + opponent as usize,
-                Shape::Scissors => {
-                    Outcome::Win as usize
-                        + match opponent {
-                            Shape::Rock => Shape::Paper,
-                            Shape::Paper => Shape::Scissors,
-                            Shape::Scissors => Shape::Rock,
-                        } as usize
-                }
+        self.total_score(&Strategy::part_2()).unwrap()
+    }
+
+    pub fn total_score(&self, strategy: &Strategy) -> Result<usize, Error> {
+        self.strategies
+            .iter()
+            .map(|&round| strategy.score(round))
+            .sum()
+    }
+
+    pub fn optimal_play(&self, strategy: &Strategy) -> Result<Play, Error> {
+        let opponents = self
+            .strategies
+            .iter()
+            .map(|&[opponent, _]| strategy.opponent_shape(opponent))
+            .collect::<Result<Vec<usize>, Error>>()?;
+        Ok(strategy.rules.optimal_play(&opponents))
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StreamingInput {
+    part_1: usize,
+    part_2: usize,
+}
+
+impl StreamingInput {
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let mut output = Self::default();
+        let (part_1, part_2) = (Strategy::part_1(), Strategy::part_2());
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
             }
-        })
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let round = parse_round(line).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("invalid round: {line}"))
+            })?;
+            let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, err);
+            output.part_1 += part_1.score(round).map_err(invalid)?;
+            output.part_2 += part_2.score(round).map_err(invalid)?;
+        }
+
+        Ok(output)
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.part_1
+    }
+
+    pub fn part_2(&self) -> usize {
+        self.part_2
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Outcome {
-    Lose = 0,
-    Draw = 3,
-    Win = 6,
+fn parse_round(line: &str) -> Option<[char; 2]> {
+    let parts = line
+        .split_whitespace()
+        .map(|part| {
+            let mut chars = part.chars();
+            match (chars.next(), chars.next()) {
+                (Some(symbol), None) => Some(symbol),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<char>>>()?;
+    match parts[..] {
+        [opponent, response] => Some([opponent, response]),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Shape {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
 }
 
-impl PartialOrd for Shape {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self {
-            Shape::Rock => match other {
-                Shape::Rock => Some(Ordering::Equal),
-                Shape::Paper => Some(Ordering::Less),
-                Shape::Scissors => Some(Ordering::Greater),
-            },
-            Shape::Paper => match other {
-                Shape::Rock => Some(Ordering::Greater),
-                Shape::Paper => Some(Ordering::Equal),
-                Shape::Scissors => Some(Ordering::Less),
-            },
-            Shape::Scissors => match other {
-                Shape::Rock => Some(Ordering::Less),
-                Shape::Paper => Some(Ordering::Greater),
-                Shape::Scissors => Some(Ordering::Equal),
-            },
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeRule {
+    pub name: String,
+    pub score: usize,
+    pub beats: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    shapes: Vec<ShapeRule>,
+    outcome_scores: [usize; 3],
+}
+
+impl Rules {
+    pub fn new(shapes: Vec<ShapeRule>, outcome_scores: [usize; 3]) -> Result<Self, Error> {
+        for (shape, rule) in shapes.iter().enumerate() {
+            if let Some(&beats) = rule.beats.iter().find(|&&beats| beats >= shapes.len()) {
+                return Err(Error::UnknownShape { shape, beats });
+            }
+        }
+        Ok(Self {
+            shapes,
+            outcome_scores,
+        })
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Self {
+            shapes: vec![
+                ShapeRule::new("Rock", 1, &[2]),
+                ShapeRule::new("Paper", 2, &[0]),
+                ShapeRule::new("Scissors", 3, &[1]),
+            ],
+            outcome_scores: [0, 3, 6],
+        }
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Self {
+            shapes: vec![
+                ShapeRule::new("Rock", 1, &[2, 3]),
+                ShapeRule::new("Paper", 2, &[0, 4]),
+                ShapeRule::new("Scissors", 3, &[1, 3]),
+                ShapeRule::new("Lizard", 4, &[1, 4]),
+                ShapeRule::new("Spock", 5, &[0, 2]),
+            ],
+            outcome_scores: [0, 3, 6],
+        }
+    }
+
+    pub fn shapes(&self) -> &[ShapeRule] {
+        &self.shapes
+    }
+
+    pub fn outcome(&self, opponent: usize, me: usize) -> Outcome {
+        if self.shapes[me].beats.contains(&opponent) {
+            Outcome::Win
+        } else if self.shapes[opponent].beats.contains(&me) {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    pub fn score(&self, opponent: usize, me: usize) -> usize {
+        self.shapes[me].score + self.outcome_scores[self.outcome(opponent, me) as usize]
+    }
+
+    pub fn shape_for(&self, opponent: usize, outcome: Outcome) -> Option<usize> {
+        (0..self.shapes.len()).find(|&me| self.outcome(opponent, me) == outcome)
+    }
+
+    pub fn best_response(&self, opponent: usize) -> usize {
+        (0..self.shapes.len()).fold(0, |best, me| {
+            if self.score(opponent, me) > self.score(opponent, best) {
+                me
+            } else {
+                best
+            }
+        })
+    }
+
+    pub fn optimal_play(&self, opponents: &[usize]) -> Play {
+        let shapes = opponents
+            .iter()
+            .map(|&opponent| self.best_response(opponent))
+            .collect::<Vec<usize>>();
+        let score = opponents
+            .iter()
+            .zip(&shapes)
+            .map(|(&opponent, &me)| self.score(opponent, me))
+            .sum();
+        Play { shapes, score }
+    }
+}
+
+impl ShapeRule {
+    pub fn new(name: &str, score: usize, beats: &[usize]) -> Self {
+        Self {
+            name: name.to_string(),
+            score,
+            beats: beats.to_vec(),
         }
     }
 }
 
-impl Ord for Shape {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Shape(Vec<char>),
+    Outcome([char; 3]),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Strategy {
+    rules: Rules,
+    opponent: Vec<char>,
+    response: Response,
+}
+
+impl Strategy {
+    pub fn new(rules: Rules, opponent: Vec<char>, response: Response) -> Result<Self, Error> {
+        // Every shape needs exactly one symbol, so each symbol maps to a shape
+        // the rules know about.
+        let shapes = rules.shapes.len();
+        let mut symbol_counts = vec![opponent.len()];
+        if let Response::Shape(symbols) = &response {
+            symbol_counts.push(symbols.len());
+        }
+        if let Some(symbols) = symbol_counts.into_iter().find(|&count| count != shapes) {
+            return Err(Error::SymbolCount { shapes, symbols });
+        }
+        Ok(Self {
+            rules,
+            opponent,
+            response,
+        })
+    }
+
+    pub fn part_1() -> Self {
+        Self {
+            rules: Rules::rock_paper_scissors(),
+            opponent: vec!['A', 'B', 'C'],
+            response: Response::Shape(vec!['X', 'Y', 'Z']),
+        }
+    }
+
+    pub fn part_2() -> Self {
+        Self {
+            rules: Rules::rock_paper_scissors(),
+            opponent: vec!['A', 'B', 'C'],
+            response: Response::Outcome(['X', 'Y', 'Z']),
+        }
+    }
+
+    pub fn opponent_shape(&self, symbol: char) -> Result<usize, Error> {
+        position(&self.opponent, symbol)
+    }
+
+    pub fn play(&self, [opponent, response]: [char; 2]) -> Result<usize, Error> {
+        let opponent = self.opponent_shape(opponent)?;
+        match &self.response {
+            Response::Shape(symbols) => position(symbols, response),
+            Response::Outcome(symbols) => {
+                let outcome =
+                    [Outcome::Lose, Outcome::Draw, Outcome::Win][position(symbols, response)?];
+                self.rules
+                    .shape_for(opponent, outcome)
+                    .ok_or(Error::Unachievable { opponent, outcome })
+            }
+        }
+    }
+
+    pub fn score(&self, round: [char; 2]) -> Result<usize, Error> {
+        let opponent = self.opponent_shape(round[0])?;
+        let me = self.play(round)?;
+        Ok(self.rules.score(opponent, me))
     }
 }
 
-impl From<&str> for Shape {
-    fn from(value: &str) -> Self {
-        match value {
-            "A" | "X" => Self::Rock,
-            "B" | "Y" => Self::Paper,
-            "C" | "Z" => Self::Scissors,
-            _ => panic!("unknown shape: {value}"),
+fn position(symbols: &[char], symbol: char) -> Result<usize, Error> {
+    symbols
+        .iter()
+        .position(|&s| s == symbol)
+        .ok_or(Error::UnknownSymbol(symbol))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Play {
+    pub shapes: Vec<usize>,
+    pub score: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    UnknownSymbol(char),
+    Unachievable { opponent: usize, outcome: Outcome },
+    UnknownShape { shape: usize, beats: usize },
+    SymbolCount { shapes: usize, symbols: usize },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownSymbol(symbol) => write!(f, "unknown symbol: '{symbol}'"),
+            Error::Unachievable { opponent, outcome } => {
+                write!(f, "no shape can {outcome:?} against shape {opponent}")
+            }
+            Error::UnknownShape { shape, beats } => {
+                write!(f, "shape {shape} beats unknown shape {beats}")
+            }
+            Error::SymbolCount { shapes, symbols } => {
+                write!(f, "{symbols} symbols given for {shapes} shapes")
+            }
         }
     }
 }
 
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,6 +370,40 @@ mod tests {
         }
     }
 
+    mod from_reader {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            expected: [usize; 2],
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                input: super::example().0,
+                expected: [15, 12],
+            })
+        }
+
+        #[test]
+        fn invalid_line() {
+            let err = StreamingInput::from_reader("A Y\nA\n".as_bytes()).unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidData, err.kind())
+        }
+
+        #[test]
+        fn actual() {
+            let streamed = StreamingInput::from_reader(open_data(DATA_DIR)).unwrap();
+            assert_eq!([PART_1, PART_2], [streamed.part_1(), streamed.part_2()])
+        }
+
+        fn run(test: &Case) {
+            let streamed = StreamingInput::from_reader(test.input.as_bytes()).unwrap();
+            assert_eq!(test.expected, [streamed.part_1(), streamed.part_2()])
+        }
+    }
+
     mod part_1 {
         use super::*;
 
@@ -157,9 +416,9 @@ mod tests {
         fn rock_rock() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Rock, Shape::Rock]],
+                    strategies: vec![['A', 'X']],
                 },
-                expected: Outcome::Draw as usize + Shape::Rock as usize,
+                expected: 3 + 1,
             });
         }
 
@@ -167,9 +426,9 @@ mod tests {
         fn rock_paper() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Rock, Shape::Paper]],
+                    strategies: vec![['A', 'Y']],
                 },
-                expected: Outcome::Win as usize + Shape::Paper as usize,
+                expected: 6 + 2,
             });
         }
 
@@ -177,19 +436,19 @@ mod tests {
         fn rock_scissors() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Rock, Shape::Scissors]],
+                    strategies: vec![['A', 'Z']],
                 },
-                expected: Outcome::Lose as usize + Shape::Scissors as usize,
+                expected: 3,
             });
         }
 
         #[test]
-        fn papoer_rock() {
+        fn paper_rock() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Paper, Shape::Rock]],
+                    strategies: vec![['B', 'X']],
                 },
-                expected: Outcome::Lose as usize + Shape::Rock as usize,
+                expected: 1,
             });
         }
 
@@ -197,9 +456,9 @@ mod tests {
         fn paper_paper() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Paper, Shape::Paper]],
+                    strategies: vec![['B', 'Y']],
                 },
-                expected: Outcome::Draw as usize + Shape::Paper as usize,
+                expected: 3 + 2,
             });
         }
 
@@ -207,9 +466,9 @@ mod tests {
         fn paper_scissors() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Paper, Shape::Scissors]],
+                    strategies: vec![['B', 'Z']],
                 },
-                expected: Outcome::Win as usize + Shape::Scissors as usize,
+                expected: 6 + 3,
             });
         }
 
@@ -217,9 +476,9 @@ mod tests {
         fn scissors_rock() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Scissors, Shape::Rock]],
+                    strategies: vec![['C', 'X']],
                 },
-                expected: Outcome::Win as usize + Shape::Rock as usize,
+                expected: 6 + 1,
             });
         }
 
@@ -227,9 +486,9 @@ mod tests {
         fn scissors_paper() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Scissors, Shape::Paper]],
+                    strategies: vec![['C', 'Y']],
                 },
-                expected: Outcome::Lose as usize + Shape::Paper as usize,
+                expected: 2,
             });
         }
 
@@ -237,9 +496,9 @@ mod tests {
         fn scissors_scissors() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Scissors, Shape::Scissors]],
+                    strategies: vec![['C', 'Z']],
                 },
-                expected: Outcome::Draw as usize + Shape::Scissors as usize,
+                expected: 3 + 3,
             });
         }
 
@@ -276,9 +535,9 @@ mod tests {
         fn rock_rock() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Rock, Shape::Rock]],
+                    strategies: vec![['A', 'X']],
                 },
-                expected: Outcome::Lose as usize + Shape::Scissors as usize,
+                expected: 3,
             });
         }
 
@@ -286,9 +545,9 @@ mod tests {
         fn rock_paper() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Rock, Shape::Paper]],
+                    strategies: vec![['A', 'Y']],
                 },
-                expected: Outcome::Draw as usize + Shape::Rock as usize,
+                expected: 3 + 1,
             });
         }
 
@@ -296,19 +555,19 @@ mod tests {
         fn rock_scissors() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Rock, Shape::Scissors]],
+                    strategies: vec![['A', 'Z']],
                 },
-                expected: Outcome::Win as usize + Shape::Paper as usize,
+                expected: 6 + 2,
             });
         }
 
         #[test]
-        fn papoer_rock() {
+        fn paper_rock() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Paper, Shape::Rock]],
+                    strategies: vec![['B', 'X']],
                 },
-                expected: Outcome::Lose as usize + Shape::Rock as usize,
+                expected: 1,
             });
         }
 
@@ -316,9 +575,9 @@ mod tests {
         fn paper_paper() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Paper, Shape::Paper]],
+                    strategies: vec![['B', 'Y']],
                 },
-                expected: Outcome::Draw as usize + Shape::Paper as usize,
+                expected: 3 + 2,
             });
         }
 
@@ -326,9 +585,9 @@ mod tests {
         fn paper_scissors() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Paper, Shape::Scissors]],
+                    strategies: vec![['B', 'Z']],
                 },
-                expected: Outcome::Win as usize + Shape::Scissors as usize,
+                expected: 6 + 3,
             });
         }
 
@@ -336,9 +595,9 @@ mod tests {
         fn scissors_rock() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Scissors, Shape::Rock]],
+                    strategies: vec![['C', 'X']],
                 },
-                expected: Outcome::Lose as usize + Shape::Paper as usize,
+                expected: 2,
             });
         }
 
@@ -346,9 +605,9 @@ mod tests {
         fn scissors_paper() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Scissors, Shape::Paper]],
+                    strategies: vec![['C', 'Y']],
                 },
-                expected: Outcome::Draw as usize + Shape::Scissors as usize,
+                expected: 3 + 3,
             });
         }
 
@@ -356,9 +615,9 @@ mod tests {
         fn scissors_scissors() {
             run(&Case {
                 data: Input {
-                    strategies: vec![[Shape::Scissors, Shape::Scissors]],
+                    strategies: vec![['C', 'Z']],
                 },
-                expected: Outcome::Win as usize + Shape::Rock as usize,
+                expected: 6 + 1,
             });
         }
 
@@ -383,17 +642,226 @@ mod tests {
         }
     }
 
+    mod rules_new {
+        use super::*;
+
+        struct Case {
+            shapes: Vec<ShapeRule>,
+            expected: Result<Rules, Error>,
+        }
+
+        #[test]
+        fn rock_paper_scissors() {
+            run(&Case {
+                shapes: Rules::rock_paper_scissors().shapes().to_vec(),
+                expected: Ok(Rules::rock_paper_scissors()),
+            })
+        }
+
+        #[test]
+        fn beats_unknown_shape() {
+            run(&Case {
+                shapes: vec![
+                    ShapeRule::new("Rock", 1, &[1]),
+                    ShapeRule::new("Paper", 2, &[0, 2]),
+                ],
+                expected: Err(Error::UnknownShape { shape: 1, beats: 2 }),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, Rules::new(test.shapes.clone(), [0, 3, 6]))
+        }
+    }
+
+    mod strategy_new {
+        use super::*;
+
+        struct Case {
+            rules: Rules,
+            opponent: Vec<char>,
+            response: Response,
+            expected: Result<(), Error>,
+        }
+
+        #[test]
+        fn lizard_spock() {
+            run(&Case {
+                rules: Rules::rock_paper_scissors_lizard_spock(),
+                opponent: vec!['A', 'B', 'C', 'D', 'E'],
+                response: Response::Outcome(['X', 'Y', 'Z']),
+                expected: Ok(()),
+            })
+        }
+
+        #[test]
+        fn too_few_opponent_symbols() {
+            run(&Case {
+                rules: Rules::rock_paper_scissors_lizard_spock(),
+                opponent: vec!['A', 'B', 'C'],
+                response: Response::Outcome(['X', 'Y', 'Z']),
+                expected: Err(Error::SymbolCount {
+                    shapes: 5,
+                    symbols: 3,
+                }),
+            })
+        }
+
+        #[test]
+        fn too_many_response_symbols() {
+            run(&Case {
+                rules: Rules::rock_paper_scissors(),
+                opponent: vec!['A', 'B', 'C'],
+                response: Response::Shape(vec!['V', 'W', 'X', 'Y', 'Z']),
+                expected: Err(Error::SymbolCount {
+                    shapes: 3,
+                    symbols: 5,
+                }),
+            })
+        }
+
+        fn run(test: &Case) {
+            let strategy = Strategy::new(
+                test.rules.clone(),
+                test.opponent.clone(),
+                test.response.clone(),
+            );
+            assert_eq!(test.expected, strategy.map(|_| ()))
+        }
+    }
+
+    mod total_score {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            strategy: Strategy,
+            expected: Result<usize, Error>,
+        }
+
+        #[test]
+        fn lizard_spock() {
+            run(&Case {
+                data: Input::from_data(
+                    "A V
+B W
+C X
+D Y
+E Z",
+                ),
+                strategy: Strategy::new(
+                    Rules::rock_paper_scissors_lizard_spock(),
+                    vec!['A', 'B', 'C', 'D', 'E'],
+                    Response::Shape(vec!['V', 'W', 'X', 'Y', 'Z']),
+                )
+                .unwrap(),
+                expected: Ok(1 + 2 + 3 + 4 + 5 + 5 * 3),
+            })
+        }
+
+        #[test]
+        fn lizard_spock_outcomes() {
+            run(&Case {
+                data: Input::from_data(
+                    "D X
+E Z",
+                ),
+                strategy: Strategy::new(
+                    Rules::rock_paper_scissors_lizard_spock(),
+                    vec!['A', 'B', 'C', 'D', 'E'],
+                    Response::Outcome(['X', 'Y', 'Z']),
+                )
+                .unwrap(),
+                // Paper is eaten by Lizard, then Paper disproves Spock.
+                expected: Ok(2 + 2 + 6),
+            })
+        }
+
+        #[test]
+        fn unknown_symbol() {
+            run(&Case {
+                data: Input::from_data("A Q"),
+                strategy: Strategy::part_1(),
+                expected: Err(Error::UnknownSymbol('Q')),
+            })
+        }
+
+        #[test]
+        fn unachievable() {
+            run(&Case {
+                data: Input::from_data("A Z"),
+                strategy: Strategy::new(
+                    Rules::new(vec![ShapeRule::new("Rock", 1, &[])], [0, 3, 6]).unwrap(),
+                    vec!['A'],
+                    Response::Outcome(['X', 'Y', 'Z']),
+                )
+                .unwrap(),
+                expected: Err(Error::Unachievable {
+                    opponent: 0,
+                    outcome: Outcome::Win,
+                }),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.data.total_score(&test.strategy))
+        }
+    }
+
+    mod optimal_play {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            strategy: Strategy,
+            expected: Play,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                strategy: Strategy::part_1(),
+                expected: Play {
+                    shapes: vec![1, 2, 0],
+                    score: 8 + 9 + 7,
+                },
+            })
+        }
+
+        #[test]
+        fn lizard_spock() {
+            run(&Case {
+                data: Input::from_data("D X"),
+                strategy: Strategy::new(
+                    Rules::rock_paper_scissors_lizard_spock(),
+                    vec!['A', 'B', 'C', 'D', 'E'],
+                    Response::Outcome(['X', 'Y', 'Z']),
+                )
+                .unwrap(),
+                // Scissors scores more than Rock when beating Lizard.
+                expected: Play {
+                    shapes: vec![2],
+                    score: 3 + 6,
+                },
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                test.data.optimal_play(&test.strategy).unwrap()
+            )
+        }
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "A Y
 B X
 C Z",
             Input {
-                strategies: vec![
-                    [Shape::Rock, Shape::Paper],
-                    [Shape::Paper, Shape::Rock],
-                    [Shape::Scissors, Shape::Scissors],
-                ],
+                strategies: vec![['A', 'Y'], ['B', 'X'], ['C', 'Z']],
             },
         )
     }
@@ -2,7 +2,7 @@ use day_03::{read_data, Input};
 
 fn main() {
     let contents = read_data("./data");
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
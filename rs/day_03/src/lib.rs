@@ -1,4 +1,9 @@
-use std::{fs::read_to_string, path::Path};
+use std::{
+    fmt::Display,
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
 
 pub const PART_1: usize = 8039;
 pub const PART_2: usize = 2510;
@@ -10,66 +15,351 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
+pub fn open_data(data_dir: &str) -> BufReader<File> {
+    BufReader::new(File::open(Path::new(data_dir).join("day_03.txt")).unwrap())
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct Input {
+    alphabet: Alphabet,
     rucksacks: Vec<Rucksack>,
 }
 
 impl Input {
-    pub fn from_data(data: &str) -> Self {
-        Self {
-            rucksacks: data.lines().map(Rucksack::from_contents).collect(),
-        }
+    pub fn from_data(data: &str) -> Result<Self, Error> {
+        Self::with_alphabet(data, Alphabet::default())
+    }
+
+    pub fn with_alphabet(data: &str, alphabet: Alphabet) -> Result<Self, Error> {
+        let rucksacks = data
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| Rucksack::from_contents(&alphabet, line, idx + 1))
+            .collect::<Result<Vec<Rucksack>, Error>>()?;
+        Ok(Self {
+            alphabet,
+            rucksacks,
+        })
     }
 
     pub fn part_1(&self) -> usize {
         self.rucksacks
             .iter()
-            .map(|r| (r.compartments[0] & r.compartments[1]).trailing_zeros() as usize)
+            .filter_map(|rucksack| rucksack.shared().lowest_priority())
+            .map(usize::from)
             .sum()
     }
 
     pub fn part_2(&self) -> usize {
-        self.rucksacks
-            .chunks_exact(3)
-            .map(|chunk| {
-                ((chunk[0].compartments[0] | chunk[0].compartments[1])
-                    & (chunk[1].compartments[0] | chunk[1].compartments[1])
-                    & (chunk[2].compartments[0] | chunk[2].compartments[1]))
-                    .trailing_zeros() as usize
-            })
+        self.groups(3)
+            .unwrap()
+            .filter_map(PrioritySet::lowest_priority)
+            .map(usize::from)
             .sum()
     }
+
+    // The common item is the lowest priority item when there are several.
+    pub fn rucksack_items(&self) -> Vec<Option<char>> {
+        self.rucksacks
+            .iter()
+            .map(|rucksack| self.lowest_item(rucksack.shared()))
+            .collect()
+    }
+
+    pub fn group_items(&self, group_size: usize) -> Result<Vec<Option<char>>, Error> {
+        Ok(self
+            .groups(group_size)?
+            .map(|group| self.lowest_item(group))
+            .collect())
+    }
+
+    fn groups(&self, group_size: usize) -> Result<impl Iterator<Item = PrioritySet> + '_, Error> {
+        if group_size == 0 {
+            return Err(Error::InvalidGroupSize(group_size));
+        }
+
+        let remainder = self.rucksacks.len() % group_size;
+        if remainder != 0 {
+            return Err(Error::IncompleteGroup {
+                group: self.rucksacks.len() / group_size + 1,
+                len: remainder,
+                expected: group_size,
+            });
+        }
+
+        Ok(self.rucksacks.chunks_exact(group_size).map(|group| {
+            group
+                .iter()
+                .map(Rucksack::all)
+                .reduce(PrioritySet::intersection)
+                .unwrap()
+        }))
+    }
+
+    fn lowest_item(&self, set: PrioritySet) -> Option<char> {
+        set.lowest_priority()
+            .and_then(|priority| self.alphabet.item(priority))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct StreamingInput {
+    group_size: usize,
+    part_1: usize,
+    part_2: usize,
+}
+
+impl StreamingInput {
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        Self::with_group_size(reader, 3)
+    }
+
+    pub fn with_group_size<R: BufRead>(mut reader: R, group_size: usize) -> io::Result<Self> {
+        if group_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                Error::InvalidGroupSize(group_size),
+            ));
+        }
+
+        let mut output = Self {
+            group_size,
+            part_1: 0,
+            part_2: 0,
+        };
+        let alphabet = Alphabet::default();
+
+        let mut line = String::new();
+        let mut line_number = 0;
+        let mut rucksack_count = 0;
+        let mut group = Vec::with_capacity(group_size);
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            line_number += 1;
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let rucksack = Rucksack::from_contents(&alphabet, line, line_number)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            rucksack_count += 1;
+            output.part_1 += rucksack.shared().lowest_priority().unwrap_or_default() as usize;
+
+            group.push(rucksack.all());
+            if group.len() == group_size {
+                let badge = group.drain(..).reduce(PrioritySet::intersection).unwrap();
+                output.part_2 += badge.lowest_priority().unwrap_or_default() as usize;
+            }
+        }
+
+        if !group.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                Error::IncompleteGroup {
+                    group: rucksack_count / group_size + 1,
+                    len: group.len(),
+                    expected: group_size,
+                },
+            ));
+        }
+
+        Ok(output)
+    }
+
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.part_1
+    }
+
+    // The sum of each group's badge priority, for groups of `group_size`.
+    pub fn part_2(&self) -> usize {
+        self.part_2
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    items: Vec<char>,
+    ascii_priorities: [u8; 128],
+}
+
+impl Alphabet {
+    pub const MAX_LEN: usize = 127;
+
+    pub fn new(items: &str) -> Result<Self, Error> {
+        let mut alphabet = Self {
+            items: Vec::new(),
+            ascii_priorities: [0; 128],
+        };
+
+        for item in items.chars() {
+            if alphabet.priority(item).is_some() {
+                return Err(Error::DuplicateItem(item));
+            }
+            alphabet.items.push(item);
+            if alphabet.items.len() > Self::MAX_LEN {
+                return Err(Error::AlphabetTooLarge(items.chars().count()));
+            }
+            if item.is_ascii() {
+                alphabet.ascii_priorities[item as usize] = alphabet.items.len() as u8;
+            }
+        }
+
+        Ok(alphabet)
+    }
+
+    pub fn priority(&self, item: char) -> Option<u8> {
+        if item.is_ascii() {
+            return Some(self.ascii_priorities[item as usize]).filter(|&p| p != 0);
+        }
+        self.items
+            .iter()
+            .position(|&i| i == item)
+            .map(|idx| idx as u8 + 1)
+    }
+
+    pub fn item(&self, priority: u8) -> Option<char> {
+        self.items
+            .get(usize::from(priority).checked_sub(1)?)
+            .copied()
+    }
+
+    pub fn priority_set(&self, items: &str) -> Result<PrioritySet, char> {
+        items.chars().try_fold(PrioritySet::default(), |acc, item| {
+            self.priority(item)
+                .map(|priority| acc.with(priority))
+                .ok_or(item)
+        })
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::new("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PrioritySet(u128);
+
+impl PrioritySet {
+    pub fn with(self, priority: u8) -> Self {
+        Self(self.0 | 1 << priority)
+    }
+
+    pub fn contains(&self, priority: u8) -> bool {
+        self.0 & 1 << priority != 0
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn lowest_priority(self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
+        }
+        Some(self.0.trailing_zeros() as u8)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 struct Rucksack {
-    compartments: [u64; 2],
+    compartments: [PrioritySet; 2],
 }
 
 impl Rucksack {
-    fn from_contents(contents: &str) -> Self {
-        let parts = contents.split_at(contents.len() / 2);
-        Self {
-            compartments: [
-                contents_to_priority_bitset(parts.0),
-                contents_to_priority_bitset(parts.1),
-            ],
+    fn from_contents(alphabet: &Alphabet, contents: &str, line: usize) -> Result<Self, Error> {
+        let item_count = contents.chars().count();
+        if !item_count.is_multiple_of(2) {
+            return Err(Error::UnevenCompartments { line });
         }
+
+        let midpoint = contents
+            .char_indices()
+            .nth(item_count / 2)
+            .map_or(contents.len(), |(idx, _)| idx);
+        let parts = contents.split_at(midpoint);
+
+        let to_set = |items| {
+            alphabet
+                .priority_set(items)
+                .map_err(|item| Error::InvalidItem { line, item })
+        };
+        Ok(Self {
+            compartments: [to_set(parts.0)?, to_set(parts.1)?],
+        })
+    }
+
+    fn shared(&self) -> PrioritySet {
+        self.compartments[0].intersection(self.compartments[1])
+    }
+
+    fn all(&self) -> PrioritySet {
+        self.compartments[0].union(self.compartments[1])
     }
 }
 
-fn contents_to_priority_bitset(contents: &str) -> u64 {
-    contents.bytes().fold(0, |acc, chr| {
-        let shift = if (b'a'..=b'z').contains(&chr) {
-            chr - b'a' + 1
-        } else {
-            chr - b'A' + 27
-        };
-        acc | (1 << shift)
-    })
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    InvalidItem {
+        line: usize,
+        item: char,
+    },
+    UnevenCompartments {
+        line: usize,
+    },
+    InvalidGroupSize(usize),
+    IncompleteGroup {
+        group: usize,
+        len: usize,
+        expected: usize,
+    },
+    DuplicateItem(char),
+    AlphabetTooLarge(usize),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidItem { line, item } => {
+                write!(f, "line {line}: item {item:?} is not in the alphabet")
+            }
+            Error::UnevenCompartments { line } => {
+                write!(f, "line {line}: rucksack has an odd number of items")
+            }
+            Error::InvalidGroupSize(size) => write!(f, "invalid group size: {size}"),
+            Error::IncompleteGroup {
+                group,
+                len,
+                expected,
+            } => write!(
+                f,
+                "group {group} has {len} rucksack(s), expected {expected}"
+            ),
+            Error::DuplicateItem(item) => write!(f, "item {item:?} appears twice in the alphabet"),
+            Error::AlphabetTooLarge(len) => write!(
+                f,
+                "alphabet has {len} items, at most {} are supported",
+                Alphabet::MAX_LEN
+            ),
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,7 +383,48 @@ mod tests {
         }
 
         fn run(test: &Case) {
-            assert_eq!(test.expected, Input::from_data(test.input))
+            assert_eq!(test.expected, Input::from_data(test.input).unwrap())
+        }
+    }
+
+    mod from_reader {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            group_size: usize,
+            expected: [usize; 2],
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                input: super::example().0,
+                group_size: 3,
+                expected: [157, 70],
+            })
+        }
+
+        #[test]
+        fn pairs() {
+            run(&Case {
+                input: super::example().0,
+                group_size: 2,
+                expected: [157, 56],
+            })
+        }
+
+        #[test]
+        fn actual() {
+            let streamed = StreamingInput::from_reader(open_data(DATA_DIR)).unwrap();
+            assert_eq!([PART_1, PART_2], [streamed.part_1(), streamed.part_2()])
+        }
+
+        fn run(test: &Case) {
+            let streamed =
+                StreamingInput::with_group_size(test.input.as_bytes(), test.group_size).unwrap();
+            assert_eq!(test.group_size, streamed.group_size());
+            assert_eq!(test.expected, [streamed.part_1(), streamed.part_2()])
         }
     }
 
@@ -116,7 +447,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_1,
             })
         }
@@ -145,7 +476,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_2,
             })
         }
@@ -155,6 +486,200 @@ mod tests {
         }
     }
 
+    mod rucksack_items {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            expected: Vec<Option<char>>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                expected: vec![
+                    Some('p'),
+                    Some('L'),
+                    Some('P'),
+                    Some('v'),
+                    Some('t'),
+                    Some('s'),
+                ],
+            })
+        }
+
+        #[test]
+        fn no_common_item() {
+            run(&Case {
+                data: Input::from_data("abcd").unwrap(),
+                expected: vec![None],
+            })
+        }
+
+        #[test]
+        fn digit_alphabet() {
+            run(&Case {
+                data: Input::with_alphabet(
+                    "1231
+4564
+7897",
+                    Alphabet::new("0123456789").unwrap(),
+                )
+                .unwrap(),
+                expected: vec![Some('1'), Some('4'), Some('7')],
+            })
+        }
+
+        #[test]
+        fn non_ascii_alphabet() {
+            run(&Case {
+                data: Input::with_alphabet("αβγα", Alphabet::new("αβγδ").unwrap()).unwrap(),
+                expected: vec![Some('α')],
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.data.rucksack_items())
+        }
+    }
+
+    mod group_items {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            group_size: usize,
+            expected: Result<Vec<Option<char>>, Error>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                group_size: 3,
+                expected: Ok(vec![Some('r'), Some('Z')]),
+            })
+        }
+
+        #[test]
+        fn pairs() {
+            run(&Case {
+                data: super::example().1,
+                group_size: 2,
+                expected: Ok(vec![Some('f'), Some('q'), Some('G')]),
+            })
+        }
+
+        #[test]
+        fn incomplete_group() {
+            run(&Case {
+                data: super::example().1,
+                group_size: 4,
+                expected: Err(Error::IncompleteGroup {
+                    group: 2,
+                    len: 2,
+                    expected: 4,
+                }),
+            })
+        }
+
+        #[test]
+        fn empty_group() {
+            run(&Case {
+                data: super::example().1,
+                group_size: 0,
+                expected: Err(Error::InvalidGroupSize(0)),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.data.group_items(test.group_size))
+        }
+    }
+
+    mod errors {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            alphabet: Result<Alphabet, Error>,
+            expected: Error,
+        }
+
+        #[test]
+        fn invalid_item() {
+            run(&Case {
+                input: "abcd\nab1a",
+                alphabet: Ok(Alphabet::default()),
+                expected: Error::InvalidItem { line: 2, item: '1' },
+            })
+        }
+
+        #[test]
+        fn uneven_compartments() {
+            run(&Case {
+                input: "abc",
+                alphabet: Ok(Alphabet::default()),
+                expected: Error::UnevenCompartments { line: 1 },
+            })
+        }
+
+        #[test]
+        fn duplicate_item() {
+            run(&Case {
+                input: "",
+                alphabet: Alphabet::new("abca"),
+                expected: Error::DuplicateItem('a'),
+            })
+        }
+
+        #[test]
+        fn alphabet_too_large() {
+            run(&Case {
+                input: "",
+                alphabet: Alphabet::new(
+                    &(0..128u32).filter_map(char::from_u32).collect::<String>(),
+                ),
+                expected: Error::AlphabetTooLarge(128),
+            })
+        }
+
+        #[test]
+        fn streaming_group_size_zero() {
+            let err = StreamingInput::with_group_size("abca".as_bytes(), 0).unwrap_err();
+            assert_eq!(
+                Some(&Error::InvalidGroupSize(0)),
+                err.get_ref().and_then(|err| err.downcast_ref::<Error>())
+            )
+        }
+
+        #[test]
+        fn streaming_incomplete_group() {
+            let err = StreamingInput::from_reader("abca\nabcb\nabcc\nabcd".as_bytes()).unwrap_err();
+            assert_eq!(
+                Some(&Error::IncompleteGroup {
+                    group: 2,
+                    len: 1,
+                    expected: 3
+                }),
+                err.get_ref().and_then(|err| err.downcast_ref::<Error>())
+            )
+        }
+
+        fn run(test: &Case) {
+            let actual = test
+                .alphabet
+                .clone()
+                .and_then(|alphabet| Input::with_alphabet(test.input, alphabet));
+            assert_eq!(Err(test.expected.clone()), actual)
+        }
+    }
+
+    fn set(items: &str) -> PrioritySet {
+        Alphabet::default().priority_set(items).unwrap()
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "vJrwpWtwJgWrhcsFMMfFFhFp
@@ -164,42 +689,25 @@ wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
 ttgJtRGJQctTZtZT
 CrZsJsPPZsGzwwsLwLmpwMDw",
             Input {
+                alphabet: Alphabet::default(),
                 rucksacks: vec![
                     Rucksack {
-                        compartments: [
-                            contents_to_priority_bitset("vJrwpWtwJgWr"),
-                            contents_to_priority_bitset("hcsFMMfFFhFp"),
-                        ],
+                        compartments: [set("vJrwpWtwJgWr"), set("hcsFMMfFFhFp")],
                     },
                     Rucksack {
-                        compartments: [
-                            contents_to_priority_bitset("jqHRNqRjqzjGDLGL"),
-                            contents_to_priority_bitset("rsFMfFZSrLrFZsSL"),
-                        ],
+                        compartments: [set("jqHRNqRjqzjGDLGL"), set("rsFMfFZSrLrFZsSL")],
                     },
                     Rucksack {
-                        compartments: [
-                            contents_to_priority_bitset("PmmdzqPrV"),
-                            contents_to_priority_bitset("vPwwTWBwg"),
-                        ],
+                        compartments: [set("PmmdzqPrV"), set("vPwwTWBwg")],
                     },
                     Rucksack {
-                        compartments: [
-                            contents_to_priority_bitset("wMqvLMZHhHMvwLH"),
-                            contents_to_priority_bitset("jbvcjnnSBnvTQFn"),
-                        ],
+                        compartments: [set("wMqvLMZHhHMvwLH"), set("jbvcjnnSBnvTQFn")],
                     },
                     Rucksack {
-                        compartments: [
-                            contents_to_priority_bitset("ttgJtRGJ"),
-                            contents_to_priority_bitset("QctTZtZT"),
-                        ],
+                        compartments: [set("ttgJtRGJ"), set("QctTZtZT")],
                     },
                     Rucksack {
-                        compartments: [
-                            contents_to_priority_bitset("CrZsJsPPZsGz"),
-                            contents_to_priority_bitset("wwsLwLmpwMDw"),
-                        ],
+                        compartments: [set("CrZsJsPPZsGz"), set("wwsLwLmpwMDw")],
                     },
                 ],
             },
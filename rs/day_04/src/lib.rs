@@ -1,4 +1,11 @@
-use std::{fs::read_to_string, path::Path};
+use std::{
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader},
+    num::NonZeroUsize,
+    path::Path,
+};
+
+use interval::{covered_at_least, Interval};
 
 pub const PART_1: usize = 511;
 pub const PART_2: usize = 821;
@@ -10,60 +17,122 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
+pub fn open_data(data_dir: &str) -> BufReader<File> {
+    BufReader::new(File::open(Path::new(data_dir).join("day_04.txt")).unwrap())
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct Input {
-    assignments: Vec<[[u8; 2]; 2]>,
+    assignments: Vec<[Interval<u32>; 2]>,
 }
 
 impl Input {
     pub fn from_data(data: &str) -> Self {
-        let mut assignments = Vec::new();
-        for line in data.lines() {
-            let (lhs_str, rhs_str) = line.split_once(',').unwrap();
-
-            let mut lhs_iter = lhs_str.split('-').map(|num| num.parse::<u8>().unwrap());
-            let min_lhs = lhs_iter.next().unwrap();
-            let max_lhs = lhs_iter.next().unwrap();
-            let lhs = [min_lhs, max_lhs];
-
-            let mut rhs_iter = rhs_str.split('-').map(|num| num.parse::<u8>().unwrap());
-            let min_rhs = rhs_iter.next().unwrap();
-            let max_rhs = rhs_iter.next().unwrap();
-            let rhs = [min_rhs, max_rhs];
-
-            let assignment = match min_lhs.cmp(&min_rhs) {
-                std::cmp::Ordering::Less => [lhs, rhs],
-                std::cmp::Ordering::Equal => {
-                    if max_lhs > max_rhs {
-                        [lhs, rhs]
-                    } else {
-                        [rhs, lhs]
-                    }
-                }
-                std::cmp::Ordering::Greater => [rhs, lhs],
-            };
-
-            assignments.push(assignment);
-        }
-
+        let assignments = data
+            .lines()
+            .map(|line| parse_assignment(line).unwrap())
+            .collect();
         Self { assignments }
     }
 
     pub fn part_1(&self) -> usize {
         self.assignments
             .iter()
-            .filter(|[[min_lhs, max_lhs], [min_rhs, max_rhs]]| {
-                min_lhs <= min_rhs && max_lhs >= max_rhs
-            })
+            .filter(|assignment| fully_contains(assignment))
             .count()
     }
 
     pub fn part_2(&self) -> usize {
         self.assignments
             .iter()
-            .filter(|[[_, max_lhs], [min_rhs, _]]| max_lhs >= min_rhs)
+            .filter(|assignment| overlaps(assignment))
             .count()
     }
+
+    pub fn overlap_sizes(&self) -> Vec<u64> {
+        self.assignments
+            .iter()
+            .map(|[lhs, rhs]| lhs.intersection(rhs).map_or(0, |shared| shared.size()))
+            .collect()
+    }
+
+    pub fn most_overlapping_pairs(&self) -> Vec<usize> {
+        let sizes = self.overlap_sizes();
+        let max = sizes.iter().copied().max().unwrap_or_default();
+        if max == 0 {
+            return Vec::new();
+        }
+        (0..sizes.len()).filter(|&idx| sizes[idx] == max).collect()
+    }
+
+    pub fn sections_covered_at_least(&self, depth: NonZeroUsize) -> u64 {
+        covered_at_least(self.assignments.iter().flatten().copied(), depth).size()
+    }
+
+    pub fn sections_covered_twice(&self) -> u64 {
+        self.sections_covered_at_least(NonZeroUsize::new(2).unwrap())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StreamingInput {
+    part_1: usize,
+    part_2: usize,
+}
+
+impl StreamingInput {
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let mut output = Self::default();
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let assignment = parse_assignment(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid assignment: {line}"),
+                )
+            })?;
+            output.part_1 += fully_contains(&assignment) as usize;
+            output.part_2 += overlaps(&assignment) as usize;
+        }
+
+        Ok(output)
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.part_1
+    }
+
+    pub fn part_2(&self) -> usize {
+        self.part_2
+    }
+}
+
+fn parse_assignment(line: &str) -> Option<[Interval<u32>; 2]> {
+    let (lhs, rhs) = line.split_once(',')?;
+    Some([parse_sections(lhs)?, parse_sections(rhs)?])
+}
+
+fn parse_sections(sections: &str) -> Option<Interval<u32>> {
+    let (min, max) = sections.split_once('-')?;
+    Interval::new(min.parse().ok()?, max.parse().ok()?)
+}
+
+fn fully_contains([lhs, rhs]: &[Interval<u32>; 2]) -> bool {
+    lhs.contains(rhs) || rhs.contains(lhs)
+}
+
+fn overlaps([lhs, rhs]: &[Interval<u32>; 2]) -> bool {
+    lhs.overlaps(rhs)
 }
 
 #[cfg(test)]
@@ -93,6 +162,40 @@ mod tests {
         }
     }
 
+    mod from_reader {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            expected: [usize; 2],
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                input: super::example().0,
+                expected: [2, 4],
+            })
+        }
+
+        #[test]
+        fn invalid_line() {
+            let err = StreamingInput::from_reader("2-4,6-8\n2-4\n".as_bytes()).unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidData, err.kind())
+        }
+
+        #[test]
+        fn actual() {
+            let streamed = StreamingInput::from_reader(open_data(DATA_DIR)).unwrap();
+            assert_eq!([PART_1, PART_2], [streamed.part_1(), streamed.part_2()])
+        }
+
+        fn run(test: &Case) {
+            let streamed = StreamingInput::from_reader(test.input.as_bytes()).unwrap();
+            assert_eq!(test.expected, [streamed.part_1(), streamed.part_2()])
+        }
+    }
+
     mod part_1 {
         use super::*;
 
@@ -151,6 +254,115 @@ mod tests {
         }
     }
 
+    mod overlap_sizes {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            expected: Vec<u64>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                expected: vec![0, 0, 1, 5, 1, 3],
+            })
+        }
+
+        #[test]
+        fn wide_sections() {
+            run(&Case {
+                data: Input::from_data("1000-70000,69000-100000"),
+                expected: vec![1001],
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.data.overlap_sizes())
+        }
+    }
+
+    mod most_overlapping_pairs {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            expected: Vec<usize>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                expected: vec![3],
+            })
+        }
+
+        #[test]
+        fn ties() {
+            run(&Case {
+                data: Input::from_data(
+                    "1-3,2-4
+1-1,3-3
+5-7,6-8",
+                ),
+                expected: vec![0, 2],
+            })
+        }
+
+        #[test]
+        fn no_overlaps() {
+            run(&Case {
+                data: Input::from_data("1-1,3-3"),
+                expected: vec![],
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.data.most_overlapping_pairs())
+        }
+    }
+
+    mod sections_covered_at_least {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            depth: NonZeroUsize,
+            expected: u64,
+        }
+
+        #[test]
+        fn example_twice() {
+            run(&Case {
+                data: super::example().1,
+                depth: NonZeroUsize::new(2).unwrap(),
+                expected: 7,
+            })
+        }
+
+        #[test]
+        fn example_six_times() {
+            run(&Case {
+                data: super::example().1,
+                depth: NonZeroUsize::new(6).unwrap(),
+                expected: 4,
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                test.data.sections_covered_at_least(test.depth)
+            )
+        }
+    }
+
+    fn sections(min: u32, max: u32) -> Interval<u32> {
+        Interval::new(min, max).unwrap()
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "2-4,6-8
@@ -161,12 +373,12 @@ mod tests {
 2-6,4-8",
             Input {
                 assignments: vec![
-                    [[2, 4], [6, 8]],
-                    [[2, 3], [4, 5]],
-                    [[5, 7], [7, 9]],
-                    [[2, 8], [3, 7]],
-                    [[4, 6], [6, 6]],
-                    [[2, 6], [4, 8]],
+                    [sections(2, 4), sections(6, 8)],
+                    [sections(2, 3), sections(4, 5)],
+                    [sections(5, 7), sections(7, 9)],
+                    [sections(2, 8), sections(3, 7)],
+                    [sections(6, 6), sections(4, 6)],
+                    [sections(2, 6), sections(4, 8)],
                 ],
             },
         )
@@ -1,9 +1,45 @@
-use day_05::{read_data, Input};
+use clap::Parser;
+use day_05::{read_data, Crane, CrateMover9000, CrateMover9001, Input};
+
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        long,
+        value_name = "MODEL",
+        value_parser = ["9000", "9001"],
+        help = "Print the stacks after every instruction using the given crane model"
+    )]
+    replay: Option<String>,
+}
 
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
+
+    let crane: Option<&dyn Crane<String>> = match opts.replay.as_deref() {
+        Some("9000") => Some(&CrateMover9000),
+        Some("9001") => Some(&CrateMover9001),
+        _ => None,
+    };
+    if let Some(crane) = crane {
+        replay(&input, crane);
+    }
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
 }
+
+fn replay(input: &Input, crane: &dyn Crane<String>) {
+    println!("{}\n", input.stacks());
+    for (step, state) in input.simulate(crane).states().enumerate() {
+        match state {
+            Ok(stacks) => println!("After step {}:\n{stacks}\n", step + 1),
+            Err(err) => {
+                eprintln!("{err}");
+                break;
+            }
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use std::{fs::read_to_string, path::Path};
+use std::{
+    fmt::{Display, Write},
+    fs::read_to_string,
+    path::Path,
+};
 
 pub const PART_1: &str = "SPFMVDTZT";
 pub const PART_2: &str = "ZFSJBPRFP";
@@ -9,91 +13,425 @@ pub fn read_data(data_dir: &str) -> String {
 
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Input {
-    crate_stacks: Vec<Vec<u8>>,
+    crate_stacks: Stacks,
     instructions: Vec<[usize; 3]>,
 }
 
 impl Input {
-    pub fn from_data(data: &str) -> Self {
-        let mut crate_stacks = Vec::new();
-        let mut line_iter = data.lines();
-        for line in &mut line_iter {
-            if crate_stacks.is_empty() {
-                crate_stacks.resize((line.len() + 1) / 4, Vec::new());
-            }
-            if line.starts_with(" 1") {
-                continue;
-            }
-            if line.is_empty() {
-                break;
-            }
+    pub fn from_data(data: &str) -> Result<Self, Error> {
+        let mut lines = (1..).zip(data.lines());
+
+        let diagram = lines
+            .by_ref()
+            .take_while(|(_, line)| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        let (&(numbering_line, numbering), crate_lines) =
+            diagram.split_last().ok_or(Error::MissingNumbering)?;
+
+        // Crates belong to the stack whose number sits within their brackets,
+        // so the layout is driven entirely by the numbering row.
+        let centres = parse_numbering(numbering_line, numbering)?;
+        let mut crate_stacks = vec![Vec::new(); centres.len()];
+        for (level, &(line_number, line)) in crate_lines.iter().rev().enumerate() {
+            for (start, end, label) in parse_crates(line_number, line)? {
+                let mut matching = centres
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &centre)| start <= centre && centre <= end)
+                    .map(|(stack, _)| stack);
+                let stack = match (matching.next(), matching.next()) {
+                    (Some(stack), None) => stack,
+                    _ => {
+                        return Err(Error::UnalignedCrate {
+                            line: line_number,
+                            column: start + 1,
+                        })
+                    }
+                };
 
-            for (idx, byte) in line.bytes().skip(1).step_by(4).enumerate() {
-                if byte == b' ' {
-                    continue;
+                let stack_crates = &mut crate_stacks[stack];
+                // Crates are read bottom-up, so anything but the next level
+                // means there is a gap underneath this one.
+                if stack_crates.len() != level {
+                    return Err(Error::FloatingCrate {
+                        line: line_number,
+                        stack: stack + 1,
+                    });
                 }
-                crate_stacks.get_mut(idx).unwrap().insert(0, byte);
+                stack_crates.push(label.to_string());
             }
         }
 
-        let mut instructions = Vec::new();
-        for instruction in line_iter {
-            let mut parts = [0; 3];
-            parts
-                .iter_mut()
-                .zip(
-                    instruction
-                        .split_ascii_whitespace()
-                        .filter_map(|chunk| chunk.parse::<usize>().ok()),
-                )
-                .for_each(|(val, num)| {
-                    *val = num;
-                });
-            instructions.push(parts);
-        }
-
-        Input {
-            crate_stacks,
+        let instructions = lines
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(line_number, line)| parse_instruction(line_number, line))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Input {
+            crate_stacks: Stacks(crate_stacks),
             instructions,
-        }
+        })
     }
 
     pub fn part_1(&self) -> String {
-        let mut stacks = self.crate_stacks.clone();
+        self.simulate(CrateMover9000).run().unwrap().tops().unwrap()
+    }
+
+    pub fn part_2(&self) -> String {
+        self.simulate(CrateMover9001).run().unwrap().tops().unwrap()
+    }
+
+    pub fn stacks(&self) -> &Stacks {
+        &self.crate_stacks
+    }
+
+    pub fn simulate<C: Crane<String>>(&self, crane: C) -> Simulation<'_, C> {
+        Simulation {
+            stacks: self.crate_stacks.clone(),
+            instructions: &self.instructions,
+            crane,
+            step: 0,
+        }
+    }
+}
 
-        for &[qty, from, to] in &self.instructions {
-            for _ in 0..qty {
-                let mover = stacks[from - 1].pop().unwrap();
-                stacks[to - 1].push(mover);
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.crate_stacks)?;
+        for [quantity, from, to] in &self.instructions {
+            write!(f, "\nmove {quantity} from {from} to {to}")?;
+        }
+        Ok(())
+    }
+}
+
+// Returns the centre column of each stack number, checking they count up from 1.
+fn parse_numbering(line_number: usize, line: &str) -> Result<Vec<usize>, Error> {
+    let mut centres = Vec::new();
+    let mut column = 0;
+    for word in line.split(' ') {
+        let start = column;
+        let width = word.chars().count();
+        column += width + 1;
+        if word.is_empty() {
+            continue;
+        }
+
+        if word.parse::<usize>().ok() != Some(centres.len() + 1) {
+            return Err(Error::InvalidNumbering {
+                line: line_number,
+                label: word.to_string(),
+            });
+        }
+        centres.push(start + (width - 1) / 2);
+    }
+
+    if centres.is_empty() {
+        return Err(Error::MissingNumbering);
+    }
+    Ok(centres)
+}
+
+// Returns the first and last column of each bracketed crate, with its label.
+fn parse_crates(line_number: usize, line: &str) -> Result<Vec<(usize, usize, &str)>, Error> {
+    let mut crates = Vec::new();
+    let mut chars = line.char_indices().enumerate();
+    while let Some((column, (open, char))) = chars.next() {
+        match char {
+            ' ' => {}
+            '[' => {
+                let (end, close) = chars
+                    .find(|&(_, (_, char))| char == ']')
+                    .map(|(end, (close, _))| (end, close))
+                    .filter(|&(_, close)| close > open + 1)
+                    .ok_or(Error::InvalidCrate {
+                        line: line_number,
+                        column: column + 1,
+                    })?;
+                crates.push((column, end, &line[open + 1..close]));
+            }
+            _ => {
+                return Err(Error::UnexpectedCharacter {
+                    line: line_number,
+                    column: column + 1,
+                    character: char,
+                })
             }
         }
+    }
+    Ok(crates)
+}
 
-        stacks
-            .iter()
-            .fold(String::with_capacity(stacks.len()), |mut acc, stack| {
-                acc.push(stack.last().copied().unwrap() as char);
-                acc
-            })
+fn parse_instruction(line_number: usize, line: &str) -> Result<[usize; 3], Error> {
+    let invalid = || Error::InvalidInstruction {
+        line: line_number,
+        text: line.to_string(),
+    };
+
+    let mut words = line.split_ascii_whitespace();
+    let mut parts = [0; 3];
+    for (keyword, part) in ["move", "from", "to"].into_iter().zip(&mut parts) {
+        if words.next() != Some(keyword) {
+            return Err(invalid());
+        }
+        *part = words
+            .next()
+            .and_then(|word| word.parse().ok())
+            .ok_or_else(invalid)?;
+    }
+    if words.next().is_some() {
+        return Err(invalid());
     }
+    Ok(parts)
+}
 
-    pub fn part_2(&self) -> String {
-        let mut stacks = self.crate_stacks.clone();
+pub trait Crane<T> {
+    // Only called once the move has been validated, so `from` always holds
+    // at least `quantity` crates.
+    fn move_crates(&self, from: &mut Vec<T>, to: &mut Vec<T>, quantity: usize);
+}
+
+impl<T, C: Crane<T> + ?Sized> Crane<T> for &C {
+    fn move_crates(&self, from: &mut Vec<T>, to: &mut Vec<T>, quantity: usize) {
+        (**self).move_crates(from, to, quantity)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrateMover9000;
+
+impl<T> Crane<T> for CrateMover9000 {
+    fn move_crates(&self, from: &mut Vec<T>, to: &mut Vec<T>, quantity: usize) {
+        for _ in 0..quantity {
+            to.push(from.pop().unwrap());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrateMover9001;
+
+impl<T> Crane<T> for CrateMover9001 {
+    fn move_crates(&self, from: &mut Vec<T>, to: &mut Vec<T>, quantity: usize) {
+        let drain_idx = from.len() - quantity;
+        to.extend(from.drain(drain_idx..));
+    }
+}
+
+#[derive(Debug)]
+pub struct Simulation<'i, C> {
+    stacks: Stacks,
+    instructions: &'i [[usize; 3]],
+    crane: C,
+    step: usize,
+}
+
+impl<'i, C: Crane<String>> Simulation<'i, C> {
+    pub fn step(&mut self) -> Option<Result<&Stacks, Error>> {
+        let &[quantity, from, to] = self.instructions.get(self.step)?;
+        self.step += 1;
+
+        let stack_count = self.stacks.0.len();
+        for stack in [from, to] {
+            if stack == 0 || stack > stack_count {
+                self.instructions = &[];
+                return Some(Err(Error::UnknownStack {
+                    step: self.step,
+                    stack,
+                }));
+            }
+        }
+
+        let available = self.stacks.0[from - 1].len();
+        if quantity > available {
+            self.instructions = &[];
+            return Some(Err(Error::NotEnoughCrates {
+                step: self.step,
+                stack: from,
+                requested: quantity,
+                available,
+            }));
+        }
 
-        for &[qty, from, to] in &self.instructions {
-            let drain_idx = stacks[from - 1].len() - qty;
-            let mut drained = stacks[from - 1].drain(drain_idx..).collect::<Vec<u8>>();
-            stacks[to - 1].append(&mut drained);
+        if from != to {
+            let (from, to) = pair_mut(&mut self.stacks.0, from - 1, to - 1);
+            self.crane.move_crates(from, to, quantity);
         }
 
-        stacks
+        Some(Ok(&self.stacks))
+    }
+
+    pub fn run(mut self) -> Result<Stacks, Error> {
+        while let Some(result) = self.step() {
+            result?;
+        }
+        Ok(self.stacks)
+    }
+
+    pub fn states(mut self) -> impl Iterator<Item = Result<Stacks, Error>> + 'i
+    where
+        C: 'i,
+    {
+        std::iter::from_fn(move || self.step().map(|result| result.cloned()))
+    }
+}
+
+fn pair_mut<T>(items: &mut [T], lhs: usize, rhs: usize) -> (&mut T, &mut T) {
+    if lhs < rhs {
+        let (left, right) = items.split_at_mut(rhs);
+        (&mut left[lhs], &mut right[0])
+    } else {
+        let (left, right) = items.split_at_mut(lhs);
+        (&mut right[0], &mut left[rhs])
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Stacks(Vec<Vec<String>>);
+
+impl Stacks {
+    pub fn new(stacks: Vec<Vec<String>>) -> Self {
+        Self(stacks)
+    }
+
+    pub fn get(&self, stack: usize) -> Option<&[String]> {
+        self.0.get(stack.checked_sub(1)?).map(Vec::as_slice)
+    }
+
+    // Reads the top crate of every stack, or nothing if any stack is empty
+    // since there'd be no telling which stack the letters came from.
+    pub fn tops(&self) -> Option<String> {
+        self.0
             .iter()
-            .fold(String::with_capacity(stacks.len()), |mut acc, stack| {
-                acc.push(stack.last().copied().unwrap() as char);
-                acc
-            })
+            .map(|stack| stack.last().map(String::as_str))
+            .collect()
+    }
+
+    // Every column is as wide as the widest crate or stack number, which keeps
+    // each number inside the brackets of the crates above it.
+    fn column_width(&self) -> usize {
+        let crate_width = self
+            .0
+            .iter()
+            .flatten()
+            .map(|label| label.chars().count() + 2)
+            .max()
+            .unwrap_or_default();
+        crate_width.max(self.0.len().to_string().len()).max(3)
+    }
+}
+
+impl Display for Stacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let height = self.0.iter().map(Vec::len).max().unwrap_or_default();
+        let width = self.column_width();
+
+        for level in (0..height).rev() {
+            for (idx, stack) in self.0.iter().enumerate() {
+                if idx > 0 {
+                    f.write_char(' ')?;
+                }
+                match stack.get(level) {
+                    Some(label) => write!(f, "{:^width$}", format!("[{label}]"))?,
+                    None => write!(f, "{:width$}", "")?,
+                }
+            }
+            f.write_char('\n')?;
+        }
+
+        for idx in 0..self.0.len() {
+            if idx > 0 {
+                f.write_char(' ')?;
+            }
+            write!(f, "{:^width$}", idx + 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    MissingNumbering,
+    InvalidNumbering {
+        line: usize,
+        label: String,
+    },
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    InvalidCrate {
+        line: usize,
+        column: usize,
+    },
+    UnalignedCrate {
+        line: usize,
+        column: usize,
+    },
+    FloatingCrate {
+        line: usize,
+        stack: usize,
+    },
+    InvalidInstruction {
+        line: usize,
+        text: String,
+    },
+    UnknownStack {
+        step: usize,
+        stack: usize,
+    },
+    NotEnoughCrates {
+        step: usize,
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MissingNumbering => f.write_str("the diagram has no stack numbering row"),
+            Error::InvalidNumbering { line, label } => {
+                write!(f, "line {line}: unexpected stack number {label:?}")
+            }
+            Error::UnexpectedCharacter {
+                line,
+                column,
+                character,
+            } => write!(f, "line {line}, column {column}: unexpected {character:?}"),
+            Error::InvalidCrate { line, column } => {
+                write!(f, "line {line}, column {column}: crate is empty or unclosed")
+            }
+            Error::UnalignedCrate { line, column } => write!(
+                f,
+                "line {line}, column {column}: crate does not sit above exactly one stack number"
+            ),
+            Error::FloatingCrate { line, stack } => {
+                write!(f, "line {line}: crate in stack {stack} has nothing beneath it")
+            }
+            Error::InvalidInstruction { line, text } => {
+                write!(f, "line {line}: invalid instruction {text:?}")
+            }
+            Error::UnknownStack { step, stack } => {
+                write!(f, "step {step}: stack {stack} does not exist")
+            }
+            Error::NotEnoughCrates {
+                step,
+                stack,
+                requested,
+                available,
+            } => write!(
+                f,
+                "step {step}: cannot move {requested} crate(s) from stack {stack}, which holds {available}"
+            ),
+        }
     }
 }
 
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,14 +443,139 @@ mod tests {
 
         struct Case<'c> {
             input: &'c str,
-            expected: Input,
+            expected: Result<Input, Error>,
         }
 
         #[test]
         fn example() {
             run(&Case {
                 input: super::example().0,
-                expected: super::example().1,
+                expected: Ok(super::example().1),
+            })
+        }
+
+        #[test]
+        fn trimmed_lines() {
+            run(&Case {
+                input: "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1",
+                expected: Ok(Input {
+                    crate_stacks: stacks(&[&["Z", "N"], &["M", "C", "D"], &["P"]]),
+                    instructions: vec![[1, 2, 1]],
+                }),
+            })
+        }
+
+        #[test]
+        fn more_than_nine_stacks() {
+            run(&Case {
+                input: "                                        [K]
+[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [L]
+ 1   2   3   4   5   6   7   8   9  10  11
+
+move 2 from 10 to 1",
+                expected: Ok(Input {
+                    crate_stacks: stacks(&[
+                        &["A"],
+                        &["B"],
+                        &["C"],
+                        &["D"],
+                        &["E"],
+                        &["F"],
+                        &["G"],
+                        &["H"],
+                        &["I"],
+                        &["J"],
+                        &["L"],
+                    ])
+                    .with_crate(11, "K"),
+                    instructions: vec![[2, 10, 1]],
+                }),
+            })
+        }
+
+        #[test]
+        fn multi_character_labels() {
+            run(&Case {
+                input: " [BB]
+[AAA]  [C]
+  1     2
+
+move 1 from 1 to 2",
+                expected: Ok(Input {
+                    crate_stacks: stacks(&[&["AAA", "BB"], &["C"]]),
+                    instructions: vec![[1, 1, 2]],
+                }),
+            })
+        }
+
+        #[test]
+        fn missing_numbering() {
+            run(&Case {
+                input: "\nmove 1 from 1 to 2",
+                expected: Err(Error::MissingNumbering),
+            })
+        }
+
+        #[test]
+        fn out_of_order_numbering() {
+            run(&Case {
+                input: "[A] [B]\n 1   3",
+                expected: Err(Error::InvalidNumbering {
+                    line: 2,
+                    label: "3".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn unexpected_character() {
+            run(&Case {
+                input: "[A] B\n 1   2",
+                expected: Err(Error::UnexpectedCharacter {
+                    line: 1,
+                    column: 5,
+                    character: 'B',
+                }),
+            })
+        }
+
+        #[test]
+        fn unclosed_crate() {
+            run(&Case {
+                input: "[A] [B\n 1   2",
+                expected: Err(Error::InvalidCrate { line: 1, column: 5 }),
+            })
+        }
+
+        #[test]
+        fn unaligned_crate() {
+            run(&Case {
+                input: "  [A]\n 1   2",
+                expected: Err(Error::UnalignedCrate { line: 1, column: 3 }),
+            })
+        }
+
+        #[test]
+        fn floating_crate() {
+            run(&Case {
+                input: "    [A]\n[B]\n 1   2",
+                expected: Err(Error::FloatingCrate { line: 1, stack: 2 }),
+            })
+        }
+
+        #[test]
+        fn invalid_instruction() {
+            run(&Case {
+                input: "[A]\n 1\n\nmove 1 from 1 to\n",
+                expected: Err(Error::InvalidInstruction {
+                    line: 4,
+                    text: "move 1 from 1 to".to_string(),
+                }),
             })
         }
 
@@ -121,6 +584,66 @@ mod tests {
         }
     }
 
+    mod serialize {
+        use super::*;
+
+        struct Case<'c> {
+            data: Input,
+            expected: &'c str,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                expected: "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2",
+            })
+        }
+
+        #[test]
+        fn more_than_nine_stacks() {
+            run(&Case {
+                data: Input {
+                    crate_stacks: stacks(&[&["A"] as &[_]; 10]).with_crate(10, "B"),
+                    instructions: vec![[1, 10, 1]],
+                },
+                expected: "                                    [B]
+[A] [A] [A] [A] [A] [A] [A] [A] [A] [A]
+ 1   2   3   4   5   6   7   8   9  10 
+
+move 1 from 10 to 1",
+            })
+        }
+
+        #[test]
+        fn multi_character_labels() {
+            run(&Case {
+                data: Input {
+                    crate_stacks: stacks(&[&["AAA", "B"], &[], &["CC"]]),
+                    instructions: vec![],
+                },
+                expected: " [B]             
+[AAA]       [CC] 
+  1     2     3  
+",
+            })
+        }
+
+        fn run(test: &Case) {
+            let serialized = test.data.to_string();
+            assert_eq!(test.expected, serialized);
+            assert_eq!(Ok(&test.data), Input::from_data(&serialized).as_ref())
+        }
+    }
+
     mod part_1 {
         use super::*;
 
@@ -140,7 +663,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_1,
             })
         }
@@ -169,7 +692,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_2,
             })
         }
@@ -179,19 +702,221 @@ mod tests {
         }
     }
 
+    mod simulate {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            expected: Vec<Result<&'static str, Error>>,
+        }
+
+        #[test]
+        fn example() {
+            run(
+                &Case {
+                    data: super::example().1,
+                    expected: vec![
+                        Ok("[D]        
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 "),
+                        Ok("        [Z]
+        [N]
+    [C] [D]
+    [M] [P]
+ 1   2   3 "),
+                        Ok("        [Z]
+        [N]
+[M]     [D]
+[C]     [P]
+ 1   2   3 "),
+                        Ok("        [Z]
+        [N]
+        [D]
+[C] [M] [P]
+ 1   2   3 "),
+                    ],
+                },
+                CrateMover9000,
+            )
+        }
+
+        #[test]
+        fn not_enough_crates() {
+            run(
+                &Case {
+                    data: Input::from_data(
+                        "[A]    
+[B] [C]
+ 1   2 
+
+move 1 from 1 to 2
+move 3 from 2 to 1
+move 1 from 1 to 2",
+                    )
+                    .unwrap(),
+                    expected: vec![
+                        Ok("    [A]
+[B] [C]
+ 1   2 "),
+                        Err(Error::NotEnoughCrates {
+                            step: 2,
+                            stack: 2,
+                            requested: 3,
+                            available: 2,
+                        }),
+                    ],
+                },
+                CrateMover9001,
+            )
+        }
+
+        #[test]
+        fn unknown_stack() {
+            run(
+                &Case {
+                    data: Input::from_data(
+                        "[A]    
+[B] [C]
+ 1   2 
+
+move 1 from 1 to 3",
+                    )
+                    .unwrap(),
+                    expected: vec![Err(Error::UnknownStack { step: 1, stack: 3 })],
+                },
+                CrateMover9000,
+            )
+        }
+
+        #[test]
+        fn custom_crane() {
+            // Moves the bottom crates of the source stack instead of the top.
+            struct Dredger;
+
+            impl<T> Crane<T> for Dredger {
+                fn move_crates(&self, from: &mut Vec<T>, to: &mut Vec<T>, quantity: usize) {
+                    to.extend(from.drain(..quantity));
+                }
+            }
+
+            let stacks = super::example().1.simulate(Dredger).run().unwrap();
+            assert_eq!(Some("DCM".to_string()), stacks.tops());
+        }
+
+        #[test]
+        fn dyn_crane() {
+            let cranes: [&dyn Crane<String>; 2] = [&CrateMover9000, &CrateMover9001];
+            let tops = cranes.map(|crane| {
+                super::example()
+                    .1
+                    .simulate(crane)
+                    .run()
+                    .unwrap()
+                    .tops()
+                    .unwrap()
+            });
+            assert_eq!(["CMZ", "MCD"], tops);
+        }
+
+        fn run<C: Crane<String>>(test: &Case, crane: C) {
+            let expected = test
+                .expected
+                .iter()
+                .map(|state| state.clone().map(str::to_string))
+                .collect::<Vec<_>>();
+            let actual = test
+                .data
+                .simulate(crane)
+                .states()
+                .map(|state| state.map(|stacks| stacks.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(expected, actual)
+        }
+    }
+
+    mod tops {
+        use super::*;
+
+        struct Case {
+            stacks: Stacks,
+            expected: Option<&'static str>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                stacks: super::example().1.stacks().clone(),
+                expected: Some("NDP"),
+            })
+        }
+
+        #[test]
+        fn empty_stack() {
+            run(&Case {
+                stacks: Stacks::new(vec![vec!["A".to_string()], vec![], vec!["C".to_string()]]),
+                expected: None,
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected.map(str::to_string), test.stacks.tops())
+        }
+    }
+
+    mod render {
+        use super::*;
+
+        struct Case<'c> {
+            data: Input,
+            expected: &'c str,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                expected: "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ",
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.data.stacks().to_string())
+        }
+    }
+
+    fn stacks(stacks: &[&[&str]]) -> Stacks {
+        Stacks(
+            stacks
+                .iter()
+                .map(|stack| stack.iter().map(|label| label.to_string()).collect())
+                .collect(),
+        )
+    }
+
+    impl Stacks {
+        fn with_crate(mut self, stack: usize, label: &str) -> Self {
+            self.0[stack - 1].push(label.to_string());
+            self
+        }
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "    [D]    
 [N] [C]    
 [Z] [M] [P]
-    1   2   3
+ 1   2   3
 
 move 1 from 2 to 1
 move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2",
             Input {
-                crate_stacks: vec![vec![b'Z', b'N'], vec![b'M', b'C', b'D'], vec![b'P']],
+                crate_stacks: stacks(&[&["Z", "N"], &["M", "C", "D"], &["P"]]),
                 instructions: vec![[1, 2, 1], [3, 1, 3], [2, 2, 1], [1, 1, 2]],
             },
         )
@@ -1,8 +1,17 @@
-use std::{fs::read_to_string, path::Path};
+use std::{
+    collections::VecDeque,
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader, Read},
+    num::NonZeroUsize,
+    path::Path,
+};
 
 pub const PART_1: usize = 1343;
 pub const PART_2: usize = 2193;
 
+pub const PACKET_MARKER: NonZeroUsize = NonZeroUsize::new(4).unwrap();
+pub const MESSAGE_MARKER: NonZeroUsize = NonZeroUsize::new(14).unwrap();
+
 pub fn read_data(data_dir: &str) -> String {
     read_to_string(Path::new(data_dir).join("day_06.txt"))
         .unwrap()
@@ -10,6 +19,10 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
+pub fn open_data(data_dir: &str) -> BufReader<File> {
+    BufReader::new(File::open(Path::new(data_dir).join("day_06.txt")).unwrap())
+}
+
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Input<'b> {
     buffer: &'b str,
@@ -21,28 +34,143 @@ impl<'b> Input<'b> {
     }
 
     pub fn part_1(&self) -> usize {
-        self.marker(4)
+        self.first_marker(PACKET_MARKER).unwrap()
     }
 
     pub fn part_2(&self) -> usize {
-        self.marker(14)
+        self.first_marker(MESSAGE_MARKER).unwrap()
+    }
+
+    pub fn first_marker(&self, marker_size: NonZeroUsize) -> Option<usize> {
+        self.markers(marker_size).next()
     }
 
-    fn marker(&self, marker_size: usize) -> usize {
+    pub fn markers(&self, marker_size: NonZeroUsize) -> impl Iterator<Item = usize> + 'b {
+        let mut detector = MarkerDetector::new(marker_size);
         self.buffer
-            .as_bytes()
-            .windows(marker_size)
-            .enumerate()
-            .find(|(_, window)| {
-                window
-                    .iter()
-                    .fold(0u32, |acc, b| acc | 1 << (b - b'a'))
-                    .count_ones()
-                    == marker_size as u32
-            })
-            .unwrap()
-            .0
-            + marker_size
+            .bytes()
+            .filter_map(move |byte| detector.push(byte))
+    }
+}
+
+// Tracks how often each byte occurs in the last `size` bytes, so sliding the
+// window along by one byte is constant time whatever the marker size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerDetector {
+    size: usize,
+    window: VecDeque<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(size: NonZeroUsize) -> Self {
+        let size = size.get();
+        Self {
+            size,
+            window: VecDeque::with_capacity(size + 1),
+            counts: [0; 256],
+            distinct: 0,
+            position: 0,
+        }
+    }
+
+    // Returns the number of bytes processed so far if they end with a marker.
+    pub fn push(&mut self, byte: u8) -> Option<usize> {
+        self.position += 1;
+
+        self.window.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+
+        if self.window.len() > self.size {
+            let oldest = self.window.pop_front().unwrap() as usize;
+            self.counts[oldest] -= 1;
+            if self.counts[oldest] == 0 {
+                self.distinct -= 1;
+            }
+        }
+
+        (self.window.len() == self.size && self.distinct == self.size).then_some(self.position)
+    }
+}
+
+pub fn markers<R: Read>(reader: R, marker_size: NonZeroUsize) -> Markers<R> {
+    Markers {
+        bytes: BufReader::new(reader).bytes(),
+        detector: MarkerDetector::new(marker_size),
+    }
+}
+
+#[derive(Debug)]
+pub struct Markers<R> {
+    bytes: io::Bytes<BufReader<R>>,
+    detector: MarkerDetector,
+}
+
+impl<R: Read> Iterator for Markers<R> {
+    type Item = io::Result<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for byte in &mut self.bytes {
+            match byte {
+                Ok(byte) => {
+                    if let Some(position) = self.detector.push(byte) {
+                        return Some(Ok(position));
+                    }
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StreamingInput {
+    part_1: Option<usize>,
+    part_2: Option<usize>,
+}
+
+impl StreamingInput {
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let mut output = Self::default();
+
+        let (mut packet, mut message) = (
+            MarkerDetector::new(PACKET_MARKER),
+            MarkerDetector::new(MESSAGE_MARKER),
+        );
+        'read: loop {
+            let buffer = reader.fill_buf()?;
+            if buffer.is_empty() {
+                break;
+            }
+            let len = buffer.len();
+
+            for &byte in buffer {
+                let packet_marker = packet.push(byte);
+                output.part_1 = output.part_1.or(packet_marker);
+                if let Some(position) = message.push(byte) {
+                    output.part_2 = Some(position);
+                    break 'read;
+                }
+            }
+
+            reader.consume(len);
+        }
+
+        Ok(output)
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.part_1.unwrap()
+    }
+
+    pub fn part_2(&self) -> usize {
+        self.part_2.unwrap()
     }
 }
 
@@ -105,6 +233,153 @@ mod tests {
         }
     }
 
+    mod from_reader {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            expected: [usize; 2],
+        }
+
+        #[test]
+        fn example_1() {
+            run(&Case {
+                input: super::example_1().0,
+                expected: [7, 19],
+            })
+        }
+
+        #[test]
+        fn example_2() {
+            run(&Case {
+                input: super::example_2().0,
+                expected: [5, 23],
+            })
+        }
+
+        #[test]
+        fn example_3() {
+            run(&Case {
+                input: super::example_3().0,
+                expected: [6, 23],
+            })
+        }
+
+        #[test]
+        fn example_4() {
+            run(&Case {
+                input: super::example_4().0,
+                expected: [10, 29],
+            })
+        }
+
+        #[test]
+        fn example_5() {
+            run(&Case {
+                input: super::example_5().0,
+                expected: [11, 26],
+            })
+        }
+
+        #[test]
+        fn small_buffer() {
+            let reader = BufReader::with_capacity(3, super::example_5().0.as_bytes());
+            let streamed = StreamingInput::from_reader(reader).unwrap();
+            assert_eq!([11, 26], [streamed.part_1(), streamed.part_2()])
+        }
+
+        #[test]
+        fn whitespace() {
+            let input = "ab\ncde fghijk\tlmnopqrstuvwxyz";
+            let in_memory = Input::from_data(input);
+            run(&Case {
+                input,
+                expected: [
+                    in_memory.first_marker(PACKET_MARKER).unwrap(),
+                    in_memory.first_marker(MESSAGE_MARKER).unwrap(),
+                ],
+            })
+        }
+
+        #[test]
+        fn actual() {
+            let streamed = StreamingInput::from_reader(open_data(DATA_DIR)).unwrap();
+            assert_eq!([PART_1, PART_2], [streamed.part_1(), streamed.part_2()])
+        }
+
+        fn run(test: &Case) {
+            let streamed = StreamingInput::from_reader(test.input.as_bytes()).unwrap();
+            assert_eq!(test.expected, [streamed.part_1(), streamed.part_2()])
+        }
+    }
+
+    mod markers {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c [u8],
+            marker_size: NonZeroUsize,
+            expected: Vec<usize>,
+        }
+
+        #[test]
+        fn example_1() {
+            run(&Case {
+                input: super::example_1().0.as_bytes(),
+                marker_size: NonZeroUsize::new(4).unwrap(),
+                expected: (7..=30).collect(),
+            })
+        }
+
+        #[test]
+        fn any_byte() {
+            run(&Case {
+                input: &[0, 0, 255, b'\n', 0, 128, 255],
+                marker_size: NonZeroUsize::new(3).unwrap(),
+                expected: vec![4, 5, 6, 7],
+            })
+        }
+
+        #[test]
+        fn long_marker() {
+            run(&Case {
+                input: b"abcabcdefghijklmnopqrstuvwxyzz",
+                marker_size: NonZeroUsize::new(26).unwrap(),
+                expected: vec![29],
+            })
+        }
+
+        #[test]
+        fn repeated_pairs() {
+            run(&Case {
+                input: b"aabbaabb",
+                marker_size: NonZeroUsize::new(2).unwrap(),
+                expected: vec![3, 5, 7],
+            })
+        }
+
+        #[test]
+        fn shorter_than_marker() {
+            run(&Case {
+                input: b"abc",
+                marker_size: NonZeroUsize::new(4).unwrap(),
+                expected: vec![],
+            })
+        }
+
+        fn run(test: &Case) {
+            let streamed = markers(test.input, test.marker_size)
+                .collect::<io::Result<Vec<_>>>()
+                .unwrap();
+            assert_eq!(test.expected, streamed);
+
+            if let Ok(buffer) = std::str::from_utf8(test.input) {
+                let in_memory = Input::from_data(buffer).markers(test.marker_size);
+                assert_eq!(test.expected, in_memory.collect::<Vec<_>>());
+            }
+        }
+    }
+
     mod part_1 {
         use super::*;
 
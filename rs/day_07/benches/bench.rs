@@ -12,12 +12,12 @@ fn bench() {
 
     let dir = "../../data";
     let contents = read_data(dir);
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
 
     let mut group = criterion.benchmark_group("Day 07");
 
     group.bench_with_input("parse contents", &contents, |b, i| {
-        b.iter(|| Input::from_data(i))
+        b.iter(|| Input::from_data(i).unwrap())
     });
 
     group.bench_with_input("part 1", &input, |b, i| {
@@ -30,7 +30,7 @@ fn bench() {
 
     group.bench_with_input("total", &contents, |b, i| {
         b.iter(|| {
-            let data = Input::from_data(i);
+            let data = Input::from_data(i).unwrap();
             assert_eq!(PART_1, data.part_1());
             assert_eq!(PART_2, data.part_2());
         })
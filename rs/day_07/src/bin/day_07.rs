@@ -1,9 +1,61 @@
-use day_07::{read_data, Input};
+use clap::Parser;
+use day_07::{read_data, Input, DISK_SIZE, REQUIRED_SPACE, SIZE_LIMIT};
+
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(long, default_value_t = SIZE_LIMIT, help = "Directory size limit for part 1")]
+    limit: usize,
+
+    #[arg(long, default_value_t = DISK_SIZE, help = "Total disk size for part 2")]
+    disk_size: usize,
+
+    #[arg(
+        long,
+        default_value_t = REQUIRED_SPACE,
+        help = "Free space required for part 2"
+    )]
+    required: usize,
+
+    #[arg(long, help = "Print the reconstructed filesystem tree")]
+    tree: bool,
+
+    #[arg(long, help = "Print the total size of every directory, like du")]
+    du: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Print the N largest files with their paths"
+    )]
+    largest: Option<usize>,
+}
 
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
+
+    let filesystem = input.filesystem();
+    match filesystem.dirs_below(opts.limit) {
+        Some(total) => println!("Part 1: {total}"),
+        None => println!("Part 1: no directory is smaller than {}", opts.limit),
+    }
+    match filesystem.smallest_to_free(opts.disk_size, opts.required) {
+        Some(size) => println!("Part 2: {size}"),
+        None => println!("Part 2: no deletion is needed, or none is enough"),
+    }
 
-    println!("Part 1: {}", input.part_1());
-    println!("Part 2: {}", input.part_2());
+    if opts.tree {
+        println!("\n{filesystem}");
+    }
+    if opts.du {
+        println!("\n{}", filesystem.du());
+    }
+    if let Some(n) = opts.largest {
+        println!();
+        for file in filesystem.largest_files(n) {
+            println!("{}\t{}", file.size(), file.path());
+        }
+    }
 }
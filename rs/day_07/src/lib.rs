@@ -1,8 +1,18 @@
-use std::{collections::HashMap, fs::read_to_string, path::Path};
+use std::{
+    cmp::Reverse,
+    fmt::{Display, Write},
+    fs::read_to_string,
+    ops::RangeBounds,
+    path::Path,
+};
 
 pub const PART_1: usize = 1315285;
 pub const PART_2: usize = 9847279;
 
+pub const SIZE_LIMIT: usize = 100_000;
+pub const DISK_SIZE: usize = 70_000_000;
+pub const REQUIRED_SPACE: usize = 30_000_000;
+
 pub fn read_data(data_dir: &str) -> String {
     read_to_string(Path::new(data_dir).join("day_07.txt"))
         .unwrap()
@@ -12,93 +22,374 @@ pub fn read_data(data_dir: &str) -> String {
 
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct Input {
-    dir_tree: HashMap<String, usize>,
+    filesystem: FileSystem,
 }
 
 impl Input {
-    pub fn from_data(data: &str) -> Self {
-        let mut dir_tree = HashMap::new();
-
-        let cur_path = &mut Path::new("").to_path_buf();
-
-        for command in data.split("$ ").skip(1) {
-            let (cmd, remainder) = command.split_once(&[' ', '\n'][..]).unwrap();
-            match cmd {
-                "ls" => {
-                    let dir_size = remainder
-                        .trim()
-                        .split_terminator('\n')
-                        .fold(0, |acc, args| {
-                            let lhs = args.split_ascii_whitespace().next().unwrap();
-                            if lhs != "dir" {
-                                acc + lhs.parse::<usize>().unwrap()
-                            } else {
-                                acc
-                            }
-                        });
-
-                    dir_tree
-                        .entry(cur_path.to_str().unwrap().to_string())
-                        .and_modify(|e| *e += dir_size)
-                        .or_insert(dir_size);
-
-                    let mut path = cur_path.as_path();
-                    loop {
-                        path = match path.parent() {
-                            Some(path) => path,
-                            None => break,
-                        };
-
-                        dir_tree
-                            .entry(path.to_str().unwrap().to_string())
-                            .and_modify(|e| *e += dir_size)
-                            .or_insert(dir_size);
+    pub fn from_data(data: &str) -> Result<Self, Error> {
+        Ok(Self {
+            filesystem: FileSystem::from_transcript(data)?,
+        })
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.filesystem.dirs_below(SIZE_LIMIT).unwrap_or(0)
+    }
+
+    pub fn part_2(&self) -> usize {
+        self.filesystem
+            .smallest_to_free(DISK_SIZE, REQUIRED_SPACE)
+            .unwrap()
+    }
+
+    pub fn filesystem(&self) -> &FileSystem {
+        &self.filesystem
+    }
+}
+
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node {
+    name: String,
+    parent: Option<usize>,
+    // Directories hold the total size of everything beneath them.
+    size: usize,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    Dir { children: Vec<usize>, listed: bool },
+    File,
+}
+
+// Nodes are stored in the order they were discovered, so every node comes
+// after its parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                size: 0,
+                kind: Kind::Dir {
+                    children: Vec::new(),
+                    listed: false,
+                },
+            }],
+        }
+    }
+}
+
+impl FileSystem {
+    pub fn from_transcript(transcript: &str) -> Result<Self, Error> {
+        let mut filesystem = Self::default();
+
+        let mut cwd = ROOT;
+        let mut listing = None;
+        for (line_number, line) in (1..).zip(transcript.lines()) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(command) = line.strip_prefix("$ ") {
+                listing = None;
+                let mut words = command.split_ascii_whitespace();
+                match (words.next(), words.next(), words.next()) {
+                    (Some("cd"), Some("/"), None) => cwd = ROOT,
+                    (Some("cd"), Some(".."), None) => {
+                        cwd = filesystem.nodes[cwd]
+                            .parent
+                            .ok_or(Error::AboveRoot { line: line_number })?;
                     }
-                }
-                _ => {
-                    let args = remainder.trim();
-                    match args {
-                        "/" => *cur_path = Path::new("/").to_path_buf(),
-                        ".." => {
-                            cur_path.pop();
-                        }
-                        into => {
-                            cur_path.push(into);
+                    (Some("cd"), Some(name), None) => {
+                        cwd = filesystem
+                            .child(cwd, name)
+                            .filter(|&child| filesystem.entry(child).is_dir())
+                            .ok_or_else(|| Error::UnknownDirectory {
+                                line: line_number,
+                                path: filesystem.entry(cwd).join(name),
+                            })?;
+                    }
+                    (Some("ls"), None, None) => {
+                        if let Kind::Dir { listed, .. } = &mut filesystem.nodes[cwd].kind {
+                            if *listed {
+                                return Err(Error::RepeatedListing {
+                                    line: line_number,
+                                    path: filesystem.entry(cwd).path(),
+                                });
+                            }
+                            *listed = true;
                         }
+                        listing = Some(cwd);
+                    }
+                    _ => {
+                        return Err(Error::UnknownCommand {
+                            line: line_number,
+                            command: command.to_string(),
+                        })
                     }
                 }
+                continue;
+            }
+
+            let dir = listing.ok_or(Error::UnexpectedOutput { line: line_number })?;
+            let invalid = || Error::InvalidEntry {
+                line: line_number,
+                text: line.to_string(),
             };
+            let (kind, name) = line.split_once(' ').ok_or_else(invalid)?;
+            let (size, kind) = match kind {
+                "dir" => (
+                    0,
+                    Kind::Dir {
+                        children: Vec::new(),
+                        listed: false,
+                    },
+                ),
+                size => (size.parse().map_err(|_| invalid())?, Kind::File),
+            };
+            if name.is_empty() || name.contains('/') || name == ".." {
+                return Err(invalid());
+            }
+            if filesystem.child(dir, name).is_some() {
+                return Err(Error::DuplicateEntry {
+                    line: line_number,
+                    path: filesystem.entry(dir).join(name),
+                });
+            }
+
+            let id = filesystem.nodes.len();
+            filesystem.nodes.push(Node {
+                name: name.to_string(),
+                parent: Some(dir),
+                size,
+                kind,
+            });
+            if let Kind::Dir { children, .. } = &mut filesystem.nodes[dir].kind {
+                children.push(id);
+            }
+        }
+
+        for id in (1..filesystem.nodes.len()).rev() {
+            let Node { parent, size, .. } = filesystem.nodes[id];
+            filesystem.nodes[parent.unwrap()].size += size;
         }
 
-        Self { dir_tree }
+        Ok(filesystem)
     }
 
-    pub fn part_1(&self) -> usize {
-        let size_limit = 100_000;
+    pub fn root(&self) -> Entry<'_> {
+        self.entry(ROOT)
+    }
 
-        self.dir_tree
-            .values()
-            .filter(|&&size| size < size_limit)
-            .sum()
+    pub fn entries(&self) -> impl Iterator<Item = Entry<'_>> {
+        (0..self.nodes.len()).map(|id| self.entry(id))
     }
 
-    pub fn part_2(&self) -> usize {
-        let target_total_space = 40_000_000;
+    pub fn dirs(&self) -> impl Iterator<Item = Entry<'_>> {
+        self.entries().filter(Entry::is_dir)
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = Entry<'_>> {
+        self.entries().filter(|entry| !entry.is_dir())
+    }
 
-        let current_total_space = self.dir_tree.get("/").unwrap();
+    pub fn largest_files(&self, n: usize) -> Vec<Entry<'_>> {
+        let mut files = self.files().collect::<Vec<_>>();
+        files.sort_by_key(|file| Reverse(file.size()));
+        files.truncate(n);
+        files
+    }
+
+    pub fn find(&self, size: impl RangeBounds<usize>) -> impl Iterator<Item = Entry<'_>> {
+        self.entries()
+            .filter(move |entry| size.contains(&entry.size()))
+    }
 
-        let target = current_total_space - target_total_space;
+    // Total size of the directories smaller than `limit`, if there are any.
+    pub fn dirs_below(&self, limit: usize) -> Option<usize> {
+        self.find(..limit)
+            .filter(Entry::is_dir)
+            .map(|dir| dir.size())
+            .reduce(|total, size| total + size)
+    }
 
-        // smallest dir larger than target
-        self.dir_tree
-            .values()
-            .filter(|&&size| size >= target)
+    // Size of the smallest directory whose deletion leaves at least `required`
+    // free on a disk of `total`. `None` if there is already enough free space,
+    // the tree does not fit on the disk, or no single directory is big enough.
+    pub fn smallest_to_free(&self, total: usize, required: usize) -> Option<usize> {
+        let free = total.checked_sub(self.root().size())?;
+        let target = required.checked_sub(free).filter(|&target| target > 0)?;
+
+        self.find(target..)
+            .filter(Entry::is_dir)
+            .map(|dir| dir.size())
             .min()
-            .copied()
-            .unwrap()
+    }
+
+    pub fn du(&self) -> Du<'_> {
+        Du(self)
+    }
+
+    fn entry(&self, id: usize) -> Entry<'_> {
+        Entry {
+            filesystem: self,
+            id,
+        }
+    }
+
+    fn child(&self, dir: usize, name: &str) -> Option<usize> {
+        self.entry(dir)
+            .children()
+            .find(|child| child.name() == name)
+            .map(|child| child.id)
     }
 }
 
+// Renders the tree in the same format as the puzzle's description.
+impl Display for FileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_entry(
+            f: &mut std::fmt::Formatter<'_>,
+            entry: Entry,
+            depth: usize,
+        ) -> std::fmt::Result {
+            if depth > 0 {
+                f.write_char('\n')?;
+            }
+            write!(f, "{:indent$}- {}", "", entry.name(), indent = depth * 2)?;
+            if entry.is_dir() {
+                f.write_str(" (dir)")?;
+                for child in entry.children() {
+                    write_entry(f, child, depth + 1)?;
+                }
+                Ok(())
+            } else {
+                write!(f, " (file, size={})", entry.size())
+            }
+        }
+
+        write_entry(f, self.root(), 0)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Entry<'f> {
+    filesystem: &'f FileSystem,
+    id: usize,
+}
+
+impl<'f> Entry<'f> {
+    pub fn name(&self) -> &'f str {
+        &self.node().name
+    }
+
+    pub fn size(&self) -> usize {
+        self.node().size
+    }
+
+    pub fn is_dir(&self) -> bool {
+        matches!(self.node().kind, Kind::Dir { .. })
+    }
+
+    pub fn parent(&self) -> Option<Entry<'f>> {
+        self.node().parent.map(|id| self.filesystem.entry(id))
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = Entry<'f>> {
+        let filesystem = self.filesystem;
+        let children = match &self.node().kind {
+            Kind::Dir { children, .. } => children.as_slice(),
+            Kind::File => &[],
+        };
+        children.iter().map(move |&id| filesystem.entry(id))
+    }
+
+    pub fn path(&self) -> String {
+        match self.parent() {
+            Some(parent) => parent.join(self.name()),
+            None => self.name().to_string(),
+        }
+    }
+
+    fn join(&self, name: &str) -> String {
+        let mut path = self.path();
+        if !path.ends_with('/') {
+            path.push('/');
+        }
+        path.push_str(name);
+        path
+    }
+
+    fn node(&self) -> &'f Node {
+        &self.filesystem.nodes[self.id]
+    }
+}
+
+// Lists every directory's total size after its contents, like `du`.
+#[derive(Debug, Clone, Copy)]
+pub struct Du<'f>(&'f FileSystem);
+
+impl Display for Du<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_dir(f: &mut std::fmt::Formatter<'_>, dir: Entry) -> std::fmt::Result {
+            for child in dir.children().filter(Entry::is_dir) {
+                write_dir(f, child)?;
+                f.write_char('\n')?;
+            }
+            write!(f, "{}\t{}", dir.size(), dir.path())
+        }
+
+        write_dir(f, self.0.root())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    UnknownCommand { line: usize, command: String },
+    UnknownDirectory { line: usize, path: String },
+    AboveRoot { line: usize },
+    RepeatedListing { line: usize, path: String },
+    UnexpectedOutput { line: usize },
+    InvalidEntry { line: usize, text: String },
+    DuplicateEntry { line: usize, path: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownCommand { line, command } => {
+                write!(f, "line {line}: unknown command {command:?}")
+            }
+            Error::UnknownDirectory { line, path } => {
+                write!(f, "line {line}: {path} has not been listed as a directory")
+            }
+            Error::AboveRoot { line } => write!(f, "line {line}: cannot leave the root directory"),
+            Error::RepeatedListing { line, path } => {
+                write!(f, "line {line}: {path} has already been listed")
+            }
+            Error::UnexpectedOutput { line } => {
+                write!(f, "line {line}: output does not follow an ls command")
+            }
+            Error::InvalidEntry { line, text } => {
+                write!(f, "line {line}: invalid directory entry {text:?}")
+            }
+            Error::DuplicateEntry { line, path } => {
+                write!(f, "line {line}: {path} is listed more than once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,19 +401,262 @@ mod tests {
 
         struct Case<'c> {
             input: &'c str,
-            expected: Input,
+            expected: Result<&'c str, Error>,
         }
 
         #[test]
         fn example() {
             run(&Case {
                 input: super::example().0,
-                expected: super::example().1,
+                expected: Ok("- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - j (file, size=4060174)
+    - d.log (file, size=8033020)
+    - d.ext (file, size=5626152)
+    - k (file, size=7214296)"),
+            })
+        }
+
+        #[test]
+        fn unknown_directory() {
+            run(&Case {
+                input: "$ cd /\n$ ls\n12 a\n$ cd a",
+                expected: Err(Error::UnknownDirectory {
+                    line: 4,
+                    path: "/a".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn repeated_listing() {
+            run(&Case {
+                input: "$ ls\ndir a\n$ cd a\n$ ls\n$ cd ..\n$ cd a\n$ ls",
+                expected: Err(Error::RepeatedListing {
+                    line: 7,
+                    path: "/a".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn above_root() {
+            run(&Case {
+                input: "$ cd /\n$ cd ..",
+                expected: Err(Error::AboveRoot { line: 2 }),
+            })
+        }
+
+        #[test]
+        fn unexpected_output() {
+            run(&Case {
+                input: "$ ls\ndir a\n$ cd a\n12 b",
+                expected: Err(Error::UnexpectedOutput { line: 4 }),
+            })
+        }
+
+        #[test]
+        fn invalid_entry() {
+            run(&Case {
+                input: "$ ls\nlarge b",
+                expected: Err(Error::InvalidEntry {
+                    line: 2,
+                    text: "large b".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn duplicate_entry() {
+            run(&Case {
+                input: "$ ls\ndir b\n12 b",
+                expected: Err(Error::DuplicateEntry {
+                    line: 3,
+                    path: "/b".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn unknown_command() {
+            run(&Case {
+                input: "$ rm -rf /",
+                expected: Err(Error::UnknownCommand {
+                    line: 1,
+                    command: "rm -rf /".to_string(),
+                }),
+            })
+        }
+
+        fn run(test: &Case) {
+            let expected = test.expected.clone().map(str::to_string);
+            let actual = Input::from_data(test.input).map(|input| input.filesystem().to_string());
+            assert_eq!(expected, actual)
+        }
+    }
+
+    mod largest_files {
+        #[test]
+        fn example() {
+            let input = super::example().1;
+            let largest = input
+                .filesystem()
+                .largest_files(3)
+                .iter()
+                .map(|file| (file.path(), file.size()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                vec![
+                    ("/b.txt".to_string(), 14848514),
+                    ("/c.dat".to_string(), 8504156),
+                    ("/d/d.log".to_string(), 8033020),
+                ],
+                largest
+            )
+        }
+    }
+
+    mod find {
+        #[test]
+        fn example() {
+            let input = super::example().1;
+            let found = input
+                .filesystem()
+                .find(25_000..=100_000)
+                .map(|entry| entry.path())
+                .collect::<Vec<_>>();
+            assert_eq!(vec!["/a", "/a/f", "/a/h.lst"], found)
+        }
+    }
+
+    mod dirs_below {
+        struct Case {
+            limit: usize,
+            expected: Option<usize>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                limit: 100_000,
+                expected: Some(95437),
+            })
+        }
+
+        #[test]
+        fn exact_limit_excluded() {
+            run(&Case {
+                limit: 94_853,
+                expected: Some(584),
+            })
+        }
+
+        #[test]
+        fn none_below() {
+            run(&Case {
+                limit: 584,
+                expected: None,
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                super::example().1.filesystem().dirs_below(test.limit)
+            )
+        }
+    }
+
+    mod smallest_to_free {
+        struct Case {
+            total: usize,
+            required: usize,
+            expected: Option<usize>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                total: 70_000_000,
+                required: 30_000_000,
+                expected: Some(24933642),
+            })
+        }
+
+        #[test]
+        fn smaller_target() {
+            run(&Case {
+                total: 50_000_000,
+                required: 2_000_000,
+                expected: Some(24933642),
+            })
+        }
+
+        #[test]
+        fn tiny_target() {
+            run(&Case {
+                total: 48_381_165,
+                required: 500,
+                expected: Some(584),
+            })
+        }
+
+        #[test]
+        fn already_free() {
+            run(&Case {
+                total: 100_000_000,
+                required: 30_000_000,
+                expected: None,
+            })
+        }
+
+        #[test]
+        fn larger_than_disk() {
+            run(&Case {
+                total: 40_000_000,
+                required: 1,
+                expected: None,
+            })
+        }
+
+        #[test]
+        fn impossible() {
+            run(&Case {
+                total: 50_000_000,
+                required: 50_000_001,
+                expected: None,
             })
         }
 
         fn run(test: &Case) {
-            assert_eq!(test.expected, Input::from_data(test.input))
+            assert_eq!(
+                test.expected,
+                super::example()
+                    .1
+                    .filesystem()
+                    .smallest_to_free(test.total, test.required)
+            )
+        }
+    }
+
+    mod du {
+        #[test]
+        fn example() {
+            assert_eq!(
+                "584\t/a/e
+94853\t/a
+24933642\t/d
+48381165\t/",
+                super::example().1.filesystem().du().to_string()
+            )
         }
     }
 
@@ -142,10 +676,18 @@ mod tests {
             })
         }
 
+        #[test]
+        fn nothing_below_limit() {
+            run(&Case {
+                data: Input::from_data("$ cd /\n$ ls\n100000 a.txt").unwrap(),
+                expected: 0,
+            })
+        }
+
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_1,
             })
         }
@@ -174,7 +716,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_2,
             })
         }
@@ -185,19 +727,7 @@ mod tests {
     }
 
     fn example() -> (&'static str, Input) {
-        let dir_tree = HashMap::from_iter(
-            vec![
-                ("/", 48_381_165),
-                ("/a", 94_853),
-                ("/a/e", 584),
-                ("/d", 24_933_642),
-            ]
-            .iter()
-            .map(|(path, size)| (path.to_string(), *size as usize)),
-        );
-
-        (
-            "$ cd /
+        let input = "$ cd /
 $ ls
 dir a
 14848514 b.txt
@@ -219,8 +749,8 @@ $ ls
 4060174 j
 8033020 d.log
 5626152 d.ext
-7214296 k",
-            Input { dir_tree },
-        )
+7214296 k";
+
+        (input, Input::from_data(input).unwrap())
     }
 }
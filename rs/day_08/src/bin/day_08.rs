@@ -6,4 +6,13 @@ fn main() {
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
+
+    if let Some(tree) = input.survey().best_tree() {
+        println!(
+            "Best tree: row {}, column {} (scenic score {})",
+            tree.row + 1,
+            tree.col + 1,
+            tree.scenic_score
+        );
+    }
 }
@@ -1,4 +1,6 @@
-use std::{fs::read_to_string, path::Path};
+use std::{fmt::Display, fs::read_to_string, path::Path};
+
+use grid::{Grid, Point};
 
 pub const PART_1: usize = 1807;
 pub const PART_2: usize = 480000;
@@ -10,148 +12,127 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
-#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct Input {
-    rows: Vec<Vec<u8>>,
-    cols: Vec<Vec<u8>>,
+    heights: Grid<u8>,
 }
 
 impl Input {
     pub fn from_data(data: &str) -> Self {
-        let mut rows = Vec::new();
-        let mut cols = Vec::new();
-        for line in data.lines() {
-            if cols.is_empty() {
-                cols.resize(line.len(), Vec::new());
-            }
-            let row = line.bytes().enumerate().fold(
-                Vec::with_capacity(line.len()),
-                |mut acc, (idx, byte)| {
-                    let height = byte - b'0';
-                    cols.get_mut(idx).unwrap().push(height);
-                    acc.push(height);
-                    acc
-                },
-            );
-            rows.push(row);
+        let heights = Grid::parse(data, |char| char.to_digit(10).map(|height| height as u8));
+        Input {
+            heights: heights.unwrap(),
         }
-        Input { rows, cols }
     }
 
     pub fn part_1(&self) -> usize {
-        let mut grid = vec![vec![false; self.rows[0].len()]; self.rows.len()];
-
-        // Horizontal visibility.
-        for (row, vis) in self.rows.iter().zip(grid.iter_mut()) {
-            // Left -> Right.
-            let mut max = None;
-            for (idx, &height) in row.iter().enumerate() {
-                if match max {
-                    None => true,
-                    Some(max_height) => height > max_height,
-                } {
-                    *vis.get_mut(idx).unwrap() = true;
-                }
-                max = Some(max.unwrap_or_default().max(height))
-            }
+        self.survey().visible_count()
+    }
 
-            // Right -> Left.
-            let mut max = None;
-            for (idx, &height) in row.iter().enumerate().rev() {
-                if match max {
-                    None => true,
-                    Some(max_height) => height > max_height,
-                } {
-                    *vis.get_mut(idx).unwrap() = true;
-                }
-                max = Some(max.unwrap_or_default().max(height))
-            }
+    pub fn part_2(&self) -> usize {
+        self.survey()
+            .best_tree()
+            .map_or(0, |tree| tree.scenic_score)
+    }
+
+    pub fn survey(&self) -> Survey {
+        let (width, height) = (self.heights.width(), self.heights.height());
+        let mut survey = Survey {
+            visible: self.heights.map(|_| false),
+            scenic_scores: self.heights.map(|_| 1),
+        };
+
+        let mut stack = Vec::new();
+        for y in 0..height {
+            let line = (0..width).map(|x| (x, y));
+            self.sweep(line.clone(), &mut survey, &mut stack);
+            self.sweep(line.rev(), &mut survey, &mut stack);
+        }
+        for x in 0..width {
+            let line = (0..height).map(|y| (x, y));
+            self.sweep(line.clone(), &mut survey, &mut stack);
+            self.sweep(line.rev(), &mut survey, &mut stack);
         }
 
-        // Vertical visibility.
-        for (col_idx, col) in self.cols.iter().enumerate() {
-            // Top -> Bottom.
-            let mut max = None;
-            for (idx, &height) in col.iter().enumerate() {
-                if match max {
-                    None => true,
-                    Some(max_height) => height > max_height,
-                } {
-                    *grid.get_mut(idx).unwrap().get_mut(col_idx).unwrap() = true;
-                }
+        survey
+    }
 
-                max = Some(max.unwrap_or_default().max(height))
+    // Looks back along the line from each tree in turn. The stack only keeps
+    // trees that are taller than everything after them, so the top of the
+    // stack after popping the shorter ones is the tree that blocks the view.
+    fn sweep(
+        &self,
+        line: impl Iterator<Item = Point>,
+        survey: &mut Survey,
+        stack: &mut Vec<(usize, u8)>,
+    ) {
+        stack.clear();
+        for (position, point) in line.enumerate() {
+            let height = self.heights[point];
+            while stack.last().is_some_and(|&(_, blocker)| blocker < height) {
+                stack.pop();
             }
 
-            // Bottom -> Top.
-            let mut max = None;
-            for (idx, &height) in col.iter().enumerate().rev() {
-                if match max {
-                    None => true,
-                    Some(max_height) => height > max_height,
-                } {
-                    *grid.get_mut(idx).unwrap().get_mut(col_idx).unwrap() = true;
+            match stack.last() {
+                Some(&(blocker, _)) => survey.scenic_scores[point] *= position - blocker,
+                None => {
+                    survey.visible[point] = true;
+                    survey.scenic_scores[point] *= position;
                 }
-                max = Some(max.unwrap_or_default().max(height))
             }
+            stack.push((position, height));
         }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tree {
+    pub row: usize,
+    pub col: usize,
+    pub scenic_score: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Survey {
+    visible: Grid<bool>,
+    scenic_scores: Grid<usize>,
+}
 
-        grid.iter().flatten().filter(|&&v| v).count()
+impl Survey {
+    pub fn is_visible(&self, row: usize, col: usize) -> Option<bool> {
+        self.visible.get((col, row)).copied()
     }
 
-    pub fn part_2(&self) -> usize {
-        let mut max_visibility = 0;
-        for (x, row) in self
-            .rows
-            .iter()
-            .enumerate()
-            .skip(1)
-            .take(self.rows.len() - 2)
-        {
-            for (y, col) in self
-                .cols
-                .iter()
-                .enumerate()
-                .skip(1)
-                .take(self.cols.len() - 2)
-            {
-                let house_height = row.get(y).copied().unwrap();
-
-                let l_to_r = row
-                    .iter()
-                    .skip(y + 1)
-                    .position(|&height| height >= house_height)
-                    .map(|pos| pos + 1)
-                    .unwrap_or(row.len() - y - 1);
-
-                let r_to_l = row
-                    .iter()
-                    .take(y)
-                    .rev()
-                    .position(|&height| height >= house_height)
-                    .map(|pos| pos + 1)
-                    .unwrap_or(y);
-
-                let t_to_b = col
-                    .iter()
-                    .skip(x + 1)
-                    .position(|&height| height >= house_height)
-                    .map(|pos| pos + 1)
-                    .unwrap_or(col.len() - x - 1);
-
-                let b_to_t = col
-                    .iter()
-                    .take(x)
-                    .rev()
-                    .position(|&height| height >= house_height)
-                    .map(|pos| pos + 1)
-                    .unwrap_or(x);
-
-                max_visibility = max_visibility.max(l_to_r * r_to_l * t_to_b * b_to_t);
-            }
-        }
+    pub fn scenic_score(&self, row: usize, col: usize) -> Option<usize> {
+        self.scenic_scores.get((col, row)).copied()
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.visible.cells().filter(|(_, &visible)| visible).count()
+    }
+
+    // Ties go to the first tree in reading order.
+    pub fn best_tree(&self) -> Option<Tree> {
+        let ((col, row), &scenic_score) = self
+            .scenic_scores
+            .cells()
+            .rev()
+            .max_by_key(|&(_, score)| score)?;
+        Some(Tree {
+            row,
+            col,
+            scenic_score,
+        })
+    }
+}
 
-        max_visibility
+// Renders the visibility map, with `#` for visible trees and `.` for hidden.
+impl Display for Survey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let map = self
+            .visible
+            .render(|_, &visible| if visible { '#' } else { '.' });
+        f.write_str(&map)
     }
 }
 
@@ -240,6 +221,89 @@ mod tests {
         }
     }
 
+    mod survey {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            visibility: &'static str,
+            best_tree: Option<Tree>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                visibility: "#####
+###.#
+##.##
+#.#.#
+#####",
+                best_tree: Some(Tree {
+                    row: 3,
+                    col: 2,
+                    scenic_score: 8,
+                }),
+            })
+        }
+
+        #[test]
+        fn equal_heights() {
+            run(&Case {
+                data: Input::from_data(
+                    "5555
+5555
+5555",
+                ),
+                visibility: "####
+#..#
+####",
+                best_tree: Some(Tree {
+                    row: 1,
+                    col: 1,
+                    scenic_score: 1,
+                }),
+            })
+        }
+
+        #[test]
+        fn single_tree() {
+            run(&Case {
+                data: Input::from_data("7"),
+                visibility: "#",
+                best_tree: Some(Tree {
+                    row: 0,
+                    col: 0,
+                    scenic_score: 0,
+                }),
+            })
+        }
+
+        #[test]
+        fn empty() {
+            run(&Case {
+                data: Input::from_data(""),
+                visibility: "",
+                best_tree: None,
+            })
+        }
+
+        fn run(test: &Case) {
+            let survey = test.data.survey();
+            assert_eq!(test.visibility, survey.to_string());
+            assert_eq!(test.best_tree, survey.best_tree());
+        }
+
+        #[test]
+        fn scenic_scores() {
+            let survey = super::example().1.survey();
+            assert_eq!(Some(4), survey.scenic_score(1, 2));
+            assert_eq!(Some(0), survey.scenic_score(0, 0));
+            assert_eq!(None, survey.scenic_score(1, 5));
+            assert_eq!(Some(false), survey.is_visible(3, 3));
+        }
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "30373
@@ -248,20 +312,17 @@ mod tests {
 33549
 35390",
             Input {
-                rows: vec![
-                    vec![3, 0, 3, 7, 3],
-                    vec![2, 5, 5, 1, 2],
-                    vec![6, 5, 3, 3, 2],
-                    vec![3, 3, 5, 4, 9],
-                    vec![3, 5, 3, 9, 0],
-                ],
-                cols: vec![
-                    vec![3, 2, 6, 3, 3],
-                    vec![0, 5, 5, 3, 5],
-                    vec![3, 5, 3, 5, 3],
-                    vec![7, 1, 3, 4, 9],
-                    vec![3, 2, 2, 9, 0],
-                ],
+                heights: Grid::from_cells(
+                    5,
+                    vec![
+                        3, 0, 3, 7, 3, //
+                        2, 5, 5, 1, 2, //
+                        6, 5, 3, 3, 2, //
+                        3, 3, 5, 4, 9, //
+                        3, 5, 3, 9, 0,
+                    ],
+                )
+                .unwrap(),
             },
         )
     }
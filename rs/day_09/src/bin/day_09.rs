@@ -1,9 +1,30 @@
+use clap::Parser;
 use day_09::{read_data, Input};
 
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        long,
+        value_name = "KNOTS",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Draw the final rope with the given number of knots, and its tail's trail"
+    )]
+    render: Option<u32>,
+}
+
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
+
+    if let Some(knots) = opts.render {
+        let knots = knots as usize;
+        let rope = input.simulate(knots).unwrap();
+        println!("\n{}\n", rope.render());
+        println!("{}", rope.render_trail(knots - 1).unwrap());
+    }
 }
@@ -1,4 +1,12 @@
-use std::{collections::HashSet, fs::read_to_string, path::Path};
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use grid::SparseGrid;
 
 pub const PART_1: usize = 6494;
 pub const PART_2: usize = 2691;
@@ -10,120 +18,266 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
+pub fn open_data(data_dir: &str) -> BufReader<File> {
+    BufReader::new(File::open(Path::new(data_dir).join("day_09.txt")).unwrap())
+}
+
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Input {
-    directions: Vec<(char, u8)>,
+    directions: Vec<(Direction, u32)>,
 }
 
 impl Input {
-    pub fn from_data(data: &str) -> Self {
-        let directions = data.lines().fold(Vec::new(), |mut acc, line| {
-            let mut line_parts = line.split_ascii_whitespace();
-            acc.push((
-                line_parts.next().unwrap().chars().next().unwrap(),
-                line_parts.next().unwrap().parse::<u8>().unwrap(),
-            ));
-            acc
-        });
-        Self { directions }
+    pub fn from_data(data: &str) -> Result<Self, Error> {
+        let directions = (1..)
+            .zip(data.lines())
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(line_number, line)| parse_direction(line_number, line))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { directions })
     }
 
     pub fn part_1(&self) -> usize {
-        let mut visited = HashSet::new();
+        self.simulate(2).unwrap().visited(1).unwrap().len()
+    }
 
-        let (mut head_x, mut head_y) = (0i32, 0i32);
-        let (mut tail_x, mut tail_y) = (0i32, 0i32);
+    pub fn part_2(&self) -> usize {
+        self.simulate(10).unwrap().visited(9).unwrap().len()
+    }
 
+    // Moves a rope of `knots` knots, tracking where its tail has been.
+    pub fn simulate(&self, knots: usize) -> Result<Rope, Error> {
+        self.simulate_tracking(knots, [knots.saturating_sub(1)])
+    }
+
+    // Moves a rope of `knots` knots, tracking where each of `tracked` has been.
+    pub fn simulate_tracking(
+        &self,
+        knots: usize,
+        tracked: impl IntoIterator<Item = usize>,
+    ) -> Result<Rope, Error> {
+        let mut rope = Rope::tracking(knots, tracked)?;
         for &(direction, amount) in &self.directions {
             for _ in 0..amount {
-                match direction {
-                    'U' => {
-                        head_y += 1;
-                    }
-                    'D' => {
-                        head_y -= 1;
-                    }
-                    'L' => {
-                        head_x -= 1;
-                    }
-                    'R' => {
-                        head_x += 1;
-                    }
-                    _ => unreachable!(),
-                }
-
-                if tail_x.abs_diff(head_x) == 2 {
-                    tail_x += (head_x - tail_x).signum();
-                    tail_y = head_y;
-                }
-                if tail_y.abs_diff(head_y) == 2 {
-                    tail_y += (head_y - tail_y).signum();
-                    tail_x = head_x;
-                }
-
-                visited.insert((tail_x, tail_y));
+                rope.step(direction);
+            }
+        }
+        Ok(rope)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StreamingInput {
+    part_1: usize,
+    part_2: usize,
+}
+
+impl StreamingInput {
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        // The knot behind the head moves the same whatever the rope's length,
+        // so one rope answers both parts.
+        let mut rope = Rope::tracking(10, [1, 9]).unwrap();
+
+        let mut line = String::new();
+        let mut line_number = 0;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            line_number += 1;
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (direction, amount) = parse_direction(line_number, line)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            for _ in 0..amount {
+                rope.step(direction);
             }
         }
 
-        visited.len()
+        Ok(Self {
+            part_1: rope.visited(1).unwrap().len(),
+            part_2: rope.visited(9).unwrap().len(),
+        })
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.part_1
     }
 
     pub fn part_2(&self) -> usize {
-        let mut visited = HashSet::new();
+        self.part_2
+    }
+}
 
-        let mut rope = [(0i32, 0i32); 10];
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
 
-        for &(direction, amount) in &self.directions {
-            for _ in 0..amount {
-                match direction {
-                    'U' => {
-                        rope[0].1 += 1;
-                    }
-                    'D' => {
-                        rope[0].1 -= 1;
-                    }
-                    'L' => {
-                        rope[0].0 -= 1;
-                    }
-                    'R' => {
-                        rope[0].0 += 1;
-                    }
-                    _ => unreachable!(),
-                }
-
-                for idx in 1..rope.len() {
-                    let (head_x, head_y) = rope[idx - 1];
-                    let (mut tail_x, mut tail_y) = rope[idx];
-
-                    if tail_x.abs_diff(head_x) < 2 && tail_y.abs_diff(head_y) < 2 {
-                        continue;
-                    }
-
-                    if tail_x.abs_diff(head_x) == 2 && tail_y == head_y {
-                        tail_x += (head_x - tail_x).signum();
-                        rope[idx] = (tail_x, tail_y);
-                        continue;
-                    }
-
-                    if tail_y.abs_diff(head_y) == 2 && tail_x == head_x {
-                        tail_y += (head_y - tail_y).signum();
-                        rope[idx] = (tail_x, tail_y);
-                        continue;
-                    }
-
-                    tail_x += (head_x - tail_x).signum();
-                    tail_y += (head_y - tail_y).signum();
-
-                    rope[idx] = (tail_x, tail_y);
-                }
-                visited.insert(rope[9]);
+fn parse_direction(line_number: usize, line: &str) -> Result<(Direction, u32), Error> {
+    let invalid = || Error::InvalidMove {
+        line: line_number,
+        text: line.to_string(),
+    };
+
+    let (direction, amount) = line.trim().split_once(' ').ok_or_else(invalid)?;
+    let direction = match direction {
+        "U" => Direction::Up,
+        "D" => Direction::Down,
+        "L" => Direction::Left,
+        "R" => Direction::Right,
+        _ => {
+            return Err(Error::UnknownDirection {
+                line: line_number,
+                direction: direction.to_string(),
+            })
+        }
+    };
+    Ok((direction, amount.trim().parse().map_err(|_| invalid())?))
+}
+
+// Knots are `(x, y)` with `y` growing upwards, starting from `(0, 0)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rope {
+    knots: Vec<(i32, i32)>,
+    // Only the knots asked for are tracked, keyed by their index.
+    visited: Vec<(usize, HashSet<(i32, i32)>)>,
+}
+
+impl Rope {
+    // A rope that tracks where its tail has been.
+    pub fn new(knots: usize) -> Result<Self, Error> {
+        Self::tracking(knots, [knots.saturating_sub(1)])
+    }
+
+    pub fn tracking(knots: usize, tracked: impl IntoIterator<Item = usize>) -> Result<Self, Error> {
+        if knots == 0 {
+            return Err(Error::NoKnots);
+        }
+
+        let mut visited = Vec::<(usize, HashSet<_>)>::new();
+        for knot in tracked {
+            if knot >= knots {
+                return Err(Error::UnknownKnot { knot, knots });
+            }
+            if !visited.iter().any(|&(tracked, _)| tracked == knot) {
+                visited.push((knot, HashSet::from([(0, 0)])));
             }
         }
 
-        visited.len()
+        Ok(Self {
+            knots: vec![(0, 0); knots],
+            visited,
+        })
+    }
+
+    pub fn knots(&self) -> &[(i32, i32)] {
+        &self.knots
+    }
+
+    // `None` if the knot isn't tracked.
+    pub fn visited(&self, knot: usize) -> Option<&HashSet<(i32, i32)>> {
+        self.visited
+            .iter()
+            .find(|&&(tracked, _)| tracked == knot)
+            .map(|(_, visited)| visited)
+    }
+
+    pub fn step(&mut self, direction: Direction) {
+        let head = &mut self.knots[0];
+        match direction {
+            Direction::Up => head.1 += 1,
+            Direction::Down => head.1 -= 1,
+            Direction::Left => head.0 -= 1,
+            Direction::Right => head.0 += 1,
+        }
+
+        for idx in 1..self.knots.len() {
+            let (head_x, head_y) = self.knots[idx - 1];
+            let (tail_x, tail_y) = self.knots[idx];
+            // Once a knot stays put, so does everything behind it.
+            if tail_x.abs_diff(head_x) < 2 && tail_y.abs_diff(head_y) < 2 {
+                break;
+            }
+
+            let tail = (
+                tail_x + (head_x - tail_x).signum(),
+                tail_y + (head_y - tail_y).signum(),
+            );
+            self.knots[idx] = tail;
+        }
+
+        for (knot, visited) in &mut self.visited {
+            visited.insert(self.knots[*knot]);
+        }
+    }
+
+    // Draws the rope like the puzzle: `H` for the head, then `T` or the knot
+    // number, with earlier knots covering later ones and `s` for the start.
+    pub fn render(&self) -> String {
+        let mut grid = SparseGrid::new();
+        grid.insert((0, 0), 's');
+        for (idx, &knot) in self.knots.iter().enumerate().rev() {
+            let label = match (idx, self.knots.len()) {
+                (0, _) => 'H',
+                (1, 2) => 'T',
+                _ => char::from_digit(idx as u32, 36).unwrap_or('*'),
+            };
+            grid.insert(flip(knot), label);
+        }
+        grid.render(|_, label| label.copied().unwrap_or('.'))
+    }
+
+    // Draws the positions the knot has visited as `#`, with `s` for the start,
+    // if it's tracked.
+    pub fn render_trail(&self, knot: usize) -> Option<String> {
+        let mut grid = self
+            .visited(knot)?
+            .iter()
+            .map(|&point| (flip(point), '#'))
+            .collect::<SparseGrid<_>>();
+        grid.insert((0, 0), 's');
+        Some(grid.render(|_, label| label.copied().unwrap_or('.')))
+    }
+}
+
+// Rendering draws rows top to bottom, so the rope is turned upside down.
+fn flip((x, y): (i32, i32)) -> (i64, i64) {
+    (x as i64, -(y as i64))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    UnknownDirection { line: usize, direction: String },
+    InvalidMove { line: usize, text: String },
+    NoKnots,
+    UnknownKnot { knot: usize, knots: usize },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownDirection { line, direction } => {
+                write!(f, "line {line}: unknown direction {direction:?}")
+            }
+            Error::InvalidMove { line, text } => write!(f, "line {line}: invalid move {text:?}"),
+            Error::NoKnots => write!(f, "a rope needs at least one knot"),
+            Error::UnknownKnot { knot, knots } => {
+                write!(f, "knot {knot} is not on a rope of {knots} knots")
+            }
+        }
     }
 }
 
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,14 +289,36 @@ mod tests {
 
         struct Case<'c> {
             input: &'c str,
-            expected: Input,
+            expected: Result<Input, Error>,
         }
 
         #[test]
         fn example() {
             run(&Case {
                 input: super::example().0,
-                expected: super::example().1,
+                expected: Ok(super::example().1),
+            })
+        }
+
+        #[test]
+        fn unknown_direction() {
+            run(&Case {
+                input: "R 4\nX 2",
+                expected: Err(Error::UnknownDirection {
+                    line: 2,
+                    direction: "X".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn invalid_move() {
+            run(&Case {
+                input: "R four",
+                expected: Err(Error::InvalidMove {
+                    line: 1,
+                    text: "R four".to_string(),
+                }),
             })
         }
 
@@ -151,6 +327,34 @@ mod tests {
         }
     }
 
+    mod from_reader {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            expected: [usize; 2],
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                input: super::example().0,
+                expected: [13, 1],
+            })
+        }
+
+        #[test]
+        fn actual() {
+            let streamed = StreamingInput::from_reader(open_data(DATA_DIR)).unwrap();
+            assert_eq!([PART_1, PART_2], [streamed.part_1(), streamed.part_2()])
+        }
+
+        fn run(test: &Case) {
+            let streamed = StreamingInput::from_reader(test.input.as_bytes()).unwrap();
+            assert_eq!(test.expected, [streamed.part_1(), streamed.part_2()])
+        }
+    }
+
     mod part_1 {
         use super::*;
 
@@ -170,7 +374,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_1,
             })
         }
@@ -199,16 +403,7 @@ mod tests {
         #[test]
         fn large_example() {
             run(&Case {
-                data: Input::from_data(
-                    "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20",
-                ),
+                data: super::large_example(),
                 expected: 36,
             });
         }
@@ -216,7 +411,7 @@ U 20",
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_2,
             })
         }
@@ -226,6 +421,168 @@ U 20",
         }
     }
 
+    mod render {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            knots: usize,
+            rope: &'static str,
+            trail: &'static str,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                knots: 2,
+                rope: ".TH
+...
+s..",
+                trail: "..##.
+...##
+.####
+....#
+s###.",
+            })
+        }
+
+        #[test]
+        fn large_example() {
+            run(&Case {
+                data: super::large_example(),
+                knots: 10,
+                rope: "H...........
+1...........
+2...........
+3...........
+4...........
+5...........
+6...........
+7...........
+8...........
+9...........
+............
+............
+............
+............
+............
+...........s",
+                trail: "#.....................
+#.............###.....
+#............#...#....
+.#..........#.....#...
+..#..........#.....#..
+...#........#.......#.
+....#......s.........#
+.....#..............#.
+......#............#..
+.......#..........#...
+........#........#....
+.........########.....",
+            })
+        }
+
+        #[test]
+        fn missing_knot() {
+            let rope = super::example().1.simulate(2).unwrap();
+            assert_eq!(None, rope.render_trail(2));
+            assert_eq!(None, rope.render_trail(0));
+        }
+
+        fn run(test: &Case) {
+            let rope = test.data.simulate(test.knots).unwrap();
+            assert_eq!(test.rope, rope.render());
+            assert_eq!(
+                Some(test.trail),
+                rope.render_trail(test.knots - 1).as_deref()
+            );
+        }
+    }
+
+    mod tracking {
+        use super::*;
+
+        struct Case {
+            knots: usize,
+            tracked: Vec<usize>,
+            expected: Result<Vec<Option<usize>>, Error>,
+        }
+
+        #[test]
+        fn both_parts() {
+            run(&Case {
+                knots: 10,
+                tracked: vec![1, 9],
+                expected: Ok(vec![Some(88), Some(36)]),
+            })
+        }
+
+        #[test]
+        fn untracked_knot() {
+            run(&Case {
+                knots: 10,
+                tracked: vec![5],
+                expected: Ok(vec![None, None]),
+            })
+        }
+
+        #[test]
+        fn no_knots() {
+            run(&Case {
+                knots: 0,
+                tracked: vec![],
+                expected: Err(Error::NoKnots),
+            })
+        }
+
+        #[test]
+        fn unknown_knot() {
+            run(&Case {
+                knots: 2,
+                tracked: vec![1, 2],
+                expected: Err(Error::UnknownKnot { knot: 2, knots: 2 }),
+            })
+        }
+
+        #[test]
+        fn every_knot() {
+            let rope = super::large_example().simulate_tracking(10, 0..10).unwrap();
+            let visited = (0..10)
+                .map(|knot| rope.visited(knot).map(HashSet::len))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                [96, 88, 80, 72, 64, 56, 50, 46, 41, 36].map(Some).to_vec(),
+                visited
+            )
+        }
+
+        fn run(test: &Case) {
+            let actual = super::large_example()
+                .simulate_tracking(test.knots, test.tracked.iter().copied())
+                .map(|rope| {
+                    [1, 9]
+                        .map(|knot| rope.visited(knot).map(HashSet::len))
+                        .to_vec()
+                });
+            assert_eq!(test.expected, actual)
+        }
+    }
+
+    fn large_example() -> Input {
+        Input::from_data(
+            "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20",
+        )
+        .unwrap()
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "R 4
@@ -238,14 +595,14 @@ L 5
 R 2",
             Input {
                 directions: vec![
-                    ('R', 4),
-                    ('U', 4),
-                    ('L', 3),
-                    ('D', 1),
-                    ('R', 4),
-                    ('D', 1),
-                    ('L', 5),
-                    ('R', 2),
+                    (Direction::Right, 4),
+                    (Direction::Up, 4),
+                    (Direction::Left, 3),
+                    (Direction::Down, 1),
+                    (Direction::Right, 4),
+                    (Direction::Down, 1),
+                    (Direction::Left, 5),
+                    (Direction::Right, 2),
                 ],
             },
         )
@@ -1,5 +1,5 @@
 use criterion::Criterion;
-use day_10::{read_data, Input, PART_1, PART_2};
+use day_10::{read_data, Input, StreamingInput, PART_1, PART_2};
 
 fn main() {
     bench();
@@ -12,12 +12,12 @@ fn bench() {
 
     let dir = "../../data";
     let contents = read_data(dir);
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
 
     let mut group = criterion.benchmark_group("Day 10");
 
     group.bench_with_input("parse contents", &contents, |b, i| {
-        b.iter(|| Input::from_data(i))
+        b.iter(|| Input::from_data(i).unwrap())
     });
 
     group.bench_with_input("part 1", &input, |b, i| {
@@ -30,7 +30,15 @@ fn bench() {
 
     group.bench_with_input("total", &contents, |b, i| {
         b.iter(|| {
-            let data = Input::from_data(i);
+            let data = Input::from_data(i).unwrap();
+            assert_eq!(PART_1, data.part_1());
+            assert_eq!(PART_2, data.part_2());
+        })
+    });
+
+    group.bench_with_input("streaming total", &contents, |b, i| {
+        b.iter(|| {
+            let data = StreamingInput::from_reader(i.as_bytes()).unwrap();
             assert_eq!(PART_1, data.part_1());
             assert_eq!(PART_2, data.part_2());
         })
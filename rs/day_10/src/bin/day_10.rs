@@ -1,9 +1,30 @@
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use clap::Parser;
 use day_10::{read_data, Input};
 
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write the cycle, X register and executing instruction of every cycle to FILE"
+    )]
+    trace: Option<PathBuf>,
+}
+
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
+
+    if let Some(path) = opts.trace {
+        let file = File::create(&path).unwrap();
+        input.write_trace(BufWriter::new(file)).unwrap();
+        println!("Wrote trace to {}", path.display());
+    }
 }
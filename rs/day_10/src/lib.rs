@@ -1,42 +1,15 @@
 use std::{
     fmt::{Display, Write},
-    fs::read_to_string,
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader},
+    num::NonZeroUsize,
     path::Path,
 };
 
-pub const PART_1: usize = 14220;
-pub const PART_2: Crt = Crt([
-    [
-        '#', '#', '#', '#', '.', '#', '#', '#', '.', '.', '.', '#', '#', '.', '.', '#', '#', '#',
-        '.', '.', '#', '.', '.', '.', '.', '#', '#', '#', '#', '.', '#', '#', '#', '#', '.', '#',
-        '.', '.', '#', '.',
-    ],
-    [
-        '.', '.', '.', '#', '.', '#', '.', '.', '#', '.', '#', '.', '.', '#', '.', '#', '.', '.',
-        '#', '.', '#', '.', '.', '.', '.', '#', '.', '.', '.', '.', '.', '.', '.', '#', '.', '#',
-        '.', '.', '#', '.',
-    ],
-    [
-        '.', '.', '#', '.', '.', '#', '.', '.', '#', '.', '#', '.', '.', '#', '.', '#', '.', '.',
-        '#', '.', '#', '.', '.', '.', '.', '#', '#', '#', '.', '.', '.', '.', '#', '.', '.', '#',
-        '.', '.', '#', '.',
-    ],
-    [
-        '.', '#', '.', '.', '.', '#', '#', '#', '.', '.', '#', '#', '#', '#', '.', '#', '#', '#',
-        '.', '.', '#', '.', '.', '.', '.', '#', '.', '.', '.', '.', '.', '#', '.', '.', '.', '#',
-        '.', '.', '#', '.',
-    ],
-    [
-        '#', '.', '.', '.', '.', '#', '.', '#', '.', '.', '#', '.', '.', '#', '.', '#', '.', '#',
-        '.', '.', '#', '.', '.', '.', '.', '#', '.', '.', '.', '.', '#', '.', '.', '.', '.', '#',
-        '.', '.', '#', '.',
-    ],
-    [
-        '#', '#', '#', '#', '.', '#', '.', '.', '#', '.', '#', '.', '.', '#', '.', '#', '.', '.',
-        '#', '.', '#', '#', '#', '#', '.', '#', '.', '.', '.', '.', '#', '#', '#', '#', '.', '.',
-        '#', '#', '.', '.',
-    ],
-]);
+use grid::Grid;
+
+pub const PART_1: i64 = 14220;
+pub const PART_2: &str = "ZRARLFZU";
 
 pub fn read_data(data_dir: &str) -> String {
     read_to_string(Path::new(data_dir).join("day_10.txt"))
@@ -45,100 +18,527 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
-#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub fn open_data(data_dir: &str) -> BufReader<File> {
+    BufReader::new(File::open(Path::new(data_dir).join("day_10.txt")).unwrap())
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Input {
     instructions: Vec<Instruction>,
 }
 
 impl Input {
-    pub fn from_data(data: &str) -> Self {
-        let instructions = data.lines().map(Instruction::from).collect();
+    pub fn from_data(data: &str) -> Result<Self, Error> {
+        Self::with_instruction_set(data, &InstructionSet::default())
+    }
 
-        Self { instructions }
+    pub fn with_instruction_set(
+        data: &str,
+        instruction_set: &InstructionSet,
+    ) -> Result<Self, Error> {
+        let instructions = (1..)
+            .zip(data.lines())
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(line_number, line)| instruction_set.parse(line_number, line))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { instructions })
     }
 
-    pub fn part_1(&self) -> usize {
-        let mut x = 1;
+    pub fn part_1(&self) -> i64 {
+        let mut signal_strength = SignalStrength::default();
+        self.run(&mut [&mut signal_strength]);
+        signal_strength.total()
+    }
 
-        let mut strengths = [0; 6];
+    pub fn part_2(&self) -> String {
+        self.crt().read().unwrap()
+    }
 
-        let mut cycle = 0;
-        for instruction in &self.instructions {
-            let (cycle_add, x_add) = match instruction {
-                Instruction::Noop => (1, 0),
-                Instruction::Addx(val) => (2, *val),
-            };
-            for _ in 0..cycle_add {
-                cycle += 1;
-                let offset_cycle = cycle - 20;
-                if offset_cycle >= 0 && offset_cycle % 40 == 0 {
-                    strengths[(offset_cycle / 40) as usize] = x * cycle;
-                }
+    pub fn crt(&self) -> Crt {
+        let mut crt = Crt::default();
+        self.run(&mut [&mut crt]);
+        crt
+    }
+
+    pub fn trace(&self) -> impl Iterator<Item = State<'_>> {
+        let mut cpu = Cpu::default();
+        self.instructions
+            .iter()
+            .flat_map(move |instruction| cpu.execute(instruction))
+    }
+
+    pub fn run(&self, observers: &mut [&mut dyn Observer]) {
+        for state in self.trace() {
+            for observer in observers.iter_mut() {
+                observer.observe(&state);
             }
-            x += x_add
         }
+    }
+
+    pub fn write_trace(&self, mut writer: impl io::Write) -> io::Result<()> {
+        for state in self.trace() {
+            writeln!(writer, "{state}")?;
+        }
+        Ok(())
+    }
+}
 
-        strengths.iter().sum::<i32>() as usize
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, instruction) in self.instructions.iter().enumerate() {
+            if idx > 0 {
+                f.write_char('\n')?;
+            }
+            write!(f, "{instruction}")?;
+        }
+        Ok(())
     }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct StreamingInput {
+    part_1: i64,
+    crt: Crt,
+}
 
-    pub fn part_2(&self) -> Crt {
-        let mut crt = [['.'; 40]; 6];
+impl StreamingInput {
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let instruction_set = InstructionSet::default();
+        let mut cpu = Cpu::default();
+        let mut signal_strength = SignalStrength::default();
+        let mut crt = Crt::default();
 
-        let mut x = 1;
-        let mut cycle = 0;
-        for instruction in &self.instructions {
-            let (cycle_add, x_add) = match instruction {
-                Instruction::Noop => (1, 0),
-                Instruction::Addx(val) => (2, *val),
-            };
-            for _ in 0..cycle_add {
-                let x_pos: i32 = cycle % 40;
-                if x_pos.abs_diff(x) <= 1 {
-                    crt[(cycle / 40) as usize][x_pos as usize] = '#';
-                }
-                cycle += 1;
+        let mut line = String::new();
+        let mut line_number = 0;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            line_number += 1;
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let instruction = instruction_set
+                .parse(line_number, line)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            for state in cpu.execute(&instruction) {
+                signal_strength.observe(&state);
+                crt.observe(&state);
             }
-            x += x_add
         }
 
-        Crt(crt)
+        Ok(Self {
+            part_1: signal_strength.total(),
+            crt,
+        })
+    }
+
+    pub fn part_1(&self) -> i64 {
+        self.part_1
+    }
+
+    pub fn part_2(&self) -> String {
+        self.crt.read().unwrap()
+    }
+
+    pub fn crt(&self) -> &Crt {
+        &self.crt
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Registers {
+    pub x: i32,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Self { x: 1 }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum Instruction {
-    Noop,
-    Addx(i32),
+pub type Execute = fn(&mut Registers, &[i32]);
+
+#[derive(Debug, Clone)]
+struct Operation {
+    name: String,
+    cycles: usize,
+    operands: usize,
+    execute: Execute,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    operations: Vec<Operation>,
+}
+
+impl Default for InstructionSet {
+    fn default() -> Self {
+        Self {
+            operations: Vec::new(),
+        }
+        .define("noop", 1, 0, |_, _| {})
+        .define("addx", 2, 1, |registers, operands| {
+            registers.x += operands[0]
+        })
+    }
 }
 
-impl From<&str> for Instruction {
-    fn from(line: &str) -> Self {
-        if line == "noop" {
-            return Self::Noop;
+impl InstructionSet {
+    // Adds an instruction taking `cycles` cycles to complete, replacing any
+    // existing one with the same name. Its effect applies once it completes.
+    pub fn define(
+        mut self,
+        name: impl Into<String>,
+        cycles: usize,
+        operands: usize,
+        execute: Execute,
+    ) -> Self {
+        let operation = Operation {
+            name: name.into(),
+            cycles,
+            operands,
+            execute,
+        };
+        match self
+            .operations
+            .iter_mut()
+            .find(|existing| existing.name == operation.name)
+        {
+            Some(existing) => *existing = operation,
+            None => self.operations.push(operation),
         }
+        self
+    }
+
+    pub fn instruction(&self, name: &str, operands: &[i32]) -> Option<Instruction> {
+        let operation = self
+            .operations
+            .iter()
+            .find(|operation| operation.name == name)?;
+        (operation.operands == operands.len()).then(|| Instruction {
+            name: operation.name.clone(),
+            operands: operands.to_vec(),
+            cycles: operation.cycles,
+            execute: operation.execute,
+        })
+    }
+
+    fn parse(&self, line_number: usize, line: &str) -> Result<Instruction, Error> {
+        let mut words = line.split_ascii_whitespace();
+        let name = words.next().unwrap_or_default();
+        let operation = self
+            .operations
+            .iter()
+            .find(|operation| operation.name == name)
+            .ok_or_else(|| Error::UnknownInstruction {
+                line: line_number,
+                name: name.to_string(),
+            })?;
+
+        let operands = words
+            .map(|operand| {
+                operand.parse().map_err(|_| Error::InvalidOperand {
+                    line: line_number,
+                    operand: operand.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if operands.len() != operation.operands {
+            return Err(Error::WrongOperandCount {
+                line: line_number,
+                name: operation.name.clone(),
+                expected: operation.operands,
+                found: operands.len(),
+            });
+        }
+
+        Ok(self.instruction(name, &operands).unwrap())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    name: String,
+    operands: Vec<i32>,
+    cycles: usize,
+    execute: Execute,
+}
+
+impl Instruction {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn operands(&self) -> &[i32] {
+        &self.operands
+    }
 
-        Self::Addx(
-            line.split_ascii_whitespace()
-                .nth(1)
-                .unwrap()
-                .parse::<i32>()
-                .unwrap(),
+    pub fn cycles(&self) -> usize {
+        self.cycles
+    }
+}
+
+// Function pointers can't be compared reliably, so instructions are equal when
+// they were defined with the same name, operands and cost.
+impl PartialEq for Instruction {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.name, &self.operands, self.cycles) == (&other.name, &other.operands, other.cycles)
+    }
+}
+
+impl Eq for Instruction {}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)?;
+        for operand in &self.operands {
+            write!(f, " {operand}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cpu {
+    registers: Registers,
+    cycle: usize,
+}
+
+impl Cpu {
+    pub fn registers(&self) -> Registers {
+        self.registers
+    }
+
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    // Runs the instruction to completion, returning the state during each of
+    // the cycles it took. The registers only change once it has completed.
+    pub fn execute<'i>(&mut self, instruction: &'i Instruction) -> impl Iterator<Item = State<'i>> {
+        let registers = self.registers;
+        let start = self.cycle;
+
+        (instruction.execute)(&mut self.registers, &instruction.operands);
+        self.cycle += instruction.cycles;
+
+        (start + 1..=self.cycle).map(move |cycle| State {
+            cycle,
+            registers,
+            instruction,
+        })
+    }
+}
+
+// The CPU's state during a cycle, counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State<'i> {
+    pub cycle: usize,
+    pub registers: Registers,
+    pub instruction: &'i Instruction,
+}
+
+impl Display for State<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}",
+            self.cycle, self.registers.x, self.instruction
         )
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Crt([[char; 40]; 6]);
+pub trait Observer {
+    fn observe(&mut self, state: &State);
+}
 
-impl Display for Crt {
+// Sums the signal strength during every `interval`th cycle, starting from
+// `first` and stopping after `last`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignalStrength {
+    first: usize,
+    interval: usize,
+    last: usize,
+    total: i64,
+}
+
+impl Default for SignalStrength {
+    fn default() -> Self {
+        Self::new(20, NonZeroUsize::new(40).unwrap(), 220)
+    }
+}
+
+impl SignalStrength {
+    pub fn new(first: usize, interval: NonZeroUsize, last: usize) -> Self {
+        Self {
+            first,
+            interval: interval.get(),
+            last,
+            total: 0,
+        }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+}
+
+impl Observer for SignalStrength {
+    fn observe(&mut self, state: &State) {
+        let cycle = state.cycle;
+        if (self.first..=self.last).contains(&cycle)
+            && (cycle - self.first).is_multiple_of(self.interval)
+        {
+            self.total += cycle as i64 * state.registers.x as i64;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    UnknownInstruction {
+        line: usize,
+        name: String,
+    },
+    WrongOperandCount {
+        line: usize,
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    InvalidOperand {
+        line: usize,
+        operand: String,
+    },
+    UnrecognisedGlyphs {
+        positions: Vec<usize>,
+    },
+}
+
+impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in self.0 {
-            for chr in row {
-                f.write_char(chr)?;
+        match self {
+            Error::UnknownInstruction { line, name } => {
+                write!(f, "line {line}: unknown instruction {name:?}")
+            }
+            Error::WrongOperandCount {
+                line,
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: {name} takes {expected} operand(s), found {found}"
+            ),
+            Error::InvalidOperand { line, operand } => {
+                write!(f, "line {line}: invalid operand {operand:?}")
+            }
+            Error::UnrecognisedGlyphs { positions } => {
+                f.write_str("unrecognised glyph(s) at position(s)")?;
+                for (idx, position) in positions.iter().enumerate() {
+                    let separator = if idx == 0 { ' ' } else { ',' };
+                    write!(f, "{separator}{position}")?;
+                }
+                Ok(())
             }
-            f.write_char('\n')?;
         }
-        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crt(Grid<bool>);
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self(Grid::new(40, 6, false))
+    }
+}
+
+// The letters of the 4x6 font, each followed by a blank column.
+const FONT_LETTERS: &str = "ABCEFGHIJKLOPRSUZ";
+const FONT: [&str; 6] = [
+    ".##..###...##..####.####..##..#..#..###...##.#..#.#.....##..###..###...###.#..#.####",
+    "#..#.#..#.#..#.#....#....#..#.#..#...#.....#.#.#..#....#..#.#..#.#..#.#....#..#....#",
+    "#..#.###..#....###..###..#....####...#.....#.##...#....#..#.#..#.#..#.#....#..#...#.",
+    "####.#..#.#....#....#....#.##.#..#...#.....#.#.#..#....#..#.###..###...##..#..#..#..",
+    "#..#.#..#.#..#.#....#....#..#.#..#...#..#..#.#.#..#....#..#.#....#.#.....#.#..#.#...",
+    "#..#.###...##..####.#.....###.#..#..###..##..#..#.####..##..#....#..#.###...##..####",
+];
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_SPACING: usize = GLYPH_WIDTH + 1;
+
+impl Crt {
+    pub fn pixels(&self) -> &Grid<bool> {
+        &self.0
+    }
+
+    // Reads the screen as letters of the 4x6 font, reporting the position of
+    // every glyph that isn't one.
+    pub fn read(&self) -> Result<String, Error> {
+        let mut letters = String::new();
+        let mut unrecognised = Vec::new();
+        for position in 0..(self.0.width() + 1) / GLYPH_SPACING {
+            match self.letter_at(position * GLYPH_SPACING) {
+                Some(letter) => letters.push(letter),
+                None => unrecognised.push(position),
+            }
+        }
+
+        if unrecognised.is_empty() {
+            Ok(letters)
+        } else {
+            Err(Error::UnrecognisedGlyphs {
+                positions: unrecognised,
+            })
+        }
+    }
+
+    fn letter_at(&self, left: usize) -> Option<char> {
+        let lit = |x, y| self.0.get((x, y)).copied().unwrap_or_default();
+        if self.0.height() != FONT.len() || (0..FONT.len()).any(|y| lit(left + GLYPH_WIDTH, y)) {
+            return None;
+        }
+
+        FONT_LETTERS
+            .chars()
+            .enumerate()
+            .find(|(idx, _)| {
+                FONT.iter().enumerate().all(|(y, row)| {
+                    row[idx * GLYPH_SPACING..][..GLYPH_WIDTH]
+                        .bytes()
+                        .enumerate()
+                        .all(|(dx, pixel)| lit(left + dx, y) == (pixel == b'#'))
+                })
+            })
+            .map(|(_, letter)| letter)
+    }
+}
+
+// Lights the pixel being drawn if the sprite centred on X covers it. Cycles
+// past the last row draw nothing.
+impl Observer for Crt {
+    fn observe(&mut self, state: &State) {
+        let pixel = state.cycle - 1;
+        let col = pixel % 40;
+        if (col as i32).abs_diff(state.registers.x) <= 1 {
+            if let Some(lit) = self.0.get_mut((col, pixel / 40)) {
+                *lit = true;
+            }
+        }
+    }
+}
+
+impl Display for Crt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.render(|_, &lit| if lit { '#' } else { '.' }))?;
+        f.write_char('\n')
     }
 }
 
@@ -153,7 +553,7 @@ mod tests {
 
         struct Case<'c> {
             input: &'c str,
-            expected: Input,
+            expected: Result<Input, Error>,
         }
 
         #[test]
@@ -162,13 +562,9 @@ mod tests {
                 input: "noop
 addx 3
 addx -5",
-                expected: Input {
-                    instructions: vec![
-                        Instruction::Noop,
-                        Instruction::Addx(3),
-                        Instruction::Addx(-5),
-                    ],
-                },
+                expected: Ok(Input {
+                    instructions: vec![noop(), addx(3), addx(-5)],
+                }),
             })
         }
 
@@ -176,7 +572,42 @@ addx -5",
         fn example() {
             run(&Case {
                 input: super::example().0,
-                expected: super::example().1,
+                expected: Ok(super::example().1),
+            })
+        }
+
+        #[test]
+        fn unknown_instruction() {
+            run(&Case {
+                input: "noop\nmulx 3",
+                expected: Err(Error::UnknownInstruction {
+                    line: 2,
+                    name: "mulx".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn wrong_operand_count() {
+            run(&Case {
+                input: "addx 1 2",
+                expected: Err(Error::WrongOperandCount {
+                    line: 1,
+                    name: "addx".to_string(),
+                    expected: 1,
+                    found: 2,
+                }),
+            })
+        }
+
+        #[test]
+        fn invalid_operand() {
+            run(&Case {
+                input: "addx x",
+                expected: Err(Error::InvalidOperand {
+                    line: 1,
+                    operand: "x".to_string(),
+                }),
             })
         }
 
@@ -185,12 +616,91 @@ addx -5",
         }
     }
 
+    mod trace {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            expected: &'static str,
+        }
+
+        #[test]
+        fn small_example() {
+            run(&Case {
+                data: Input::from_data("noop\naddx 3\naddx -5").unwrap(),
+                expected: "1\t1\tnoop
+2\t1\taddx 3
+3\t1\taddx 3
+4\t4\taddx -5
+5\t4\taddx -5
+",
+            })
+        }
+
+        #[test]
+        fn custom_instructions() {
+            let instruction_set = InstructionSet::default()
+                .define("mulx", 3, 1, |registers, operands| {
+                    registers.x *= operands[0]
+                })
+                .define("noop", 0, 0, |_, _| {});
+            run(&Case {
+                data: Input::with_instruction_set("addx 2\nnoop\nmulx 4\naddx 1", &instruction_set)
+                    .unwrap(),
+                expected: "1\t1\taddx 2
+2\t1\taddx 2
+3\t3\tmulx 4
+4\t3\tmulx 4
+5\t3\tmulx 4
+6\t12\taddx 1
+7\t12\taddx 1
+",
+            })
+        }
+
+        fn run(test: &Case) {
+            let mut trace = Vec::new();
+            test.data.write_trace(&mut trace).unwrap();
+            assert_eq!(test.expected, String::from_utf8(trace).unwrap())
+        }
+    }
+
+    mod from_reader {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            expected: i64,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                input: super::example().0,
+                expected: 13140,
+            })
+        }
+
+        #[test]
+        fn actual() {
+            let streamed = StreamingInput::from_reader(open_data(DATA_DIR)).unwrap();
+            assert_eq!(PART_1, streamed.part_1());
+            assert_eq!(PART_2, streamed.part_2());
+        }
+
+        fn run(test: &Case) {
+            let streamed = StreamingInput::from_reader(test.input.as_bytes()).unwrap();
+            assert_eq!(test.expected, streamed.part_1());
+            assert_eq!(&Input::from_data(test.input).unwrap().crt(), streamed.crt());
+        }
+    }
+
     mod part_1 {
         use super::*;
 
         struct Case {
             data: Input,
-            expected: usize,
+            expected: i64,
         }
 
         #[test]
@@ -201,10 +711,18 @@ addx -5",
             })
         }
 
+        #[test]
+        fn negative_register() {
+            run(&Case {
+                data: Input::from_data(&format!("addx -3\n{}", "noop\n".repeat(220))).unwrap(),
+                expected: -1440,
+            })
+        }
+
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_1,
             })
         }
@@ -214,71 +732,122 @@ addx -5",
         }
     }
 
-    mod part_2 {
+    mod crt {
         use super::*;
 
         struct Case {
             data: Input,
-            expected: Crt,
+            expected: &'static str,
         }
 
         #[test]
         fn example() {
             run(&Case {
                 data: super::example().1,
-                expected: Crt([
-                    [
-                        '#', '#', '.', '.', '#', '#', '.', '.', '#', '#', '.', '.', '#', '#', '.',
-                        '.', '#', '#', '.', '.', '#', '#', '.', '.', '#', '#', '.', '.', '#', '#',
-                        '.', '.', '#', '#', '.', '.', '#', '#', '.', '.',
-                    ],
-                    [
-                        '#', '#', '#', '.', '.', '.', '#', '#', '#', '.', '.', '.', '#', '#', '#',
-                        '.', '.', '.', '#', '#', '#', '.', '.', '.', '#', '#', '#', '.', '.', '.',
-                        '#', '#', '#', '.', '.', '.', '#', '#', '#', '.',
-                    ],
-                    [
-                        '#', '#', '#', '#', '.', '.', '.', '.', '#', '#', '#', '#', '.', '.', '.',
-                        '.', '#', '#', '#', '#', '.', '.', '.', '.', '#', '#', '#', '#', '.', '.',
-                        '.', '.', '#', '#', '#', '#', '.', '.', '.', '.',
-                    ],
-                    [
-                        '#', '#', '#', '#', '#', '.', '.', '.', '.', '.', '#', '#', '#', '#', '#',
-                        '.', '.', '.', '.', '.', '#', '#', '#', '#', '#', '.', '.', '.', '.', '.',
-                        '#', '#', '#', '#', '#', '.', '.', '.', '.', '.',
-                    ],
-                    [
-                        '#', '#', '#', '#', '#', '#', '.', '.', '.', '.', '.', '.', '#', '#', '#',
-                        '#', '#', '#', '.', '.', '.', '.', '.', '.', '#', '#', '#', '#', '#', '#',
-                        '.', '.', '.', '.', '.', '.', '#', '#', '#', '#',
-                    ],
-                    [
-                        '#', '#', '#', '#', '#', '#', '#', '.', '.', '.', '.', '.', '.', '.', '#',
-                        '#', '#', '#', '#', '#', '#', '.', '.', '.', '.', '.', '.', '.', '#', '#',
-                        '#', '#', '#', '#', '#', '.', '.', '.', '.', '.',
-                    ],
-                ]),
+                expected: "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+",
+            })
+        }
+
+        fn run(test: &Case) {
+            let actual = test.data.crt().to_string();
+            assert_eq!(
+                test.expected, actual,
+                "\nExpected:\n{}\n\nActual:\n{}",
+                test.expected, actual
+            )
+        }
+    }
+
+    mod read {
+        use super::*;
+
+        struct Case {
+            screen: &'static str,
+            expected: Result<String, Error>,
+        }
+
+        #[test]
+        fn letters() {
+            run(&Case {
+                screen: "####.###...##..###..#....####.####.#..#.
+...#.#..#.#..#.#..#.#....#.......#.#..#.
+..#..#..#.#..#.#..#.#....###....#..#..#.
+.#...###..####.###..#....#.....#...#..#.
+#....#.#..#..#.#.#..#....#....#....#..#.
+####.#..#.#..#.#..#.####.#....####..##..",
+                expected: Ok("ZRARLFZU".to_string()),
             })
         }
 
         #[test]
-        fn actual() {
+        fn unrecognised() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
-                expected: PART_2,
+                screen: "####.###..###..###..#....####.####.#..##
+...#.#..#.#..#.#..#.#....#.......#.#..#.
+..#..#..#.#..#.#..#.#....###....#..#..#.
+.#...###..####.###..#....#.....#...#..#.
+#....#.#..#..#.#.#..#....#....#....#..#.
+####.#..#.#..#.#..#.####.#....####..##..",
+                expected: Err(Error::UnrecognisedGlyphs {
+                    positions: vec![2, 7],
+                }),
             })
         }
 
+        #[test]
+        fn whole_font() {
+            for (idx, letter) in FONT_LETTERS.chars().enumerate() {
+                let screen = FONT
+                    .iter()
+                    .map(|row| format!("{}.", &row[idx * GLYPH_SPACING..][..GLYPH_WIDTH]).repeat(8))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                assert_eq!(Ok(letter.to_string().repeat(8)), crt(&screen).read())
+            }
+        }
+
         fn run(test: &Case) {
-            let actual = test.data.part_2();
+            assert_eq!(test.expected, crt(test.screen).read())
+        }
+    }
+
+    mod part_2 {
+        use super::*;
+
+        #[test]
+        fn actual() {
             assert_eq!(
-                test.expected, actual,
-                "\nExpected:\n{}\n\nActual:\n{}",
-                test.expected, actual
+                PART_2,
+                Input::from_data(&read_data(DATA_DIR)).unwrap().part_2()
             )
         }
     }
 
+    fn crt(screen: &str) -> Crt {
+        Crt(Grid::parse(screen, |pixel| match pixel {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap())
+    }
+
+    fn noop() -> Instruction {
+        InstructionSet::default().instruction("noop", &[]).unwrap()
+    }
+
+    fn addx(value: i32) -> Instruction {
+        InstructionSet::default()
+            .instruction("addx", &[value])
+            .unwrap()
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "addx 15
@@ -429,152 +998,152 @@ noop
 noop",
             Input {
                 instructions: vec![
-                    Instruction::Addx(15),
-                    Instruction::Addx(-11),
-                    Instruction::Addx(6),
-                    Instruction::Addx(-3),
-                    Instruction::Addx(5),
-                    Instruction::Addx(-1),
-                    Instruction::Addx(-8),
-                    Instruction::Addx(13),
-                    Instruction::Addx(4),
-                    Instruction::Noop,
-                    Instruction::Addx(-1),
-                    Instruction::Addx(5),
-                    Instruction::Addx(-1),
-                    Instruction::Addx(5),
-                    Instruction::Addx(-1),
-                    Instruction::Addx(5),
-                    Instruction::Addx(-1),
-                    Instruction::Addx(5),
-                    Instruction::Addx(-1),
-                    Instruction::Addx(-35),
-                    Instruction::Addx(1),
-                    Instruction::Addx(24),
-                    Instruction::Addx(-19),
-                    Instruction::Addx(1),
-                    Instruction::Addx(16),
-                    Instruction::Addx(-11),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(21),
-                    Instruction::Addx(-15),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(-3),
-                    Instruction::Addx(9),
-                    Instruction::Addx(1),
-                    Instruction::Addx(-3),
-                    Instruction::Addx(8),
-                    Instruction::Addx(1),
-                    Instruction::Addx(5),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(-36),
-                    Instruction::Noop,
-                    Instruction::Addx(1),
-                    Instruction::Addx(7),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(2),
-                    Instruction::Addx(6),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(1),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(7),
-                    Instruction::Addx(1),
-                    Instruction::Noop,
-                    Instruction::Addx(-13),
-                    Instruction::Addx(13),
-                    Instruction::Addx(7),
-                    Instruction::Noop,
-                    Instruction::Addx(1),
-                    Instruction::Addx(-33),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(2),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(8),
-                    Instruction::Noop,
-                    Instruction::Addx(-1),
-                    Instruction::Addx(2),
-                    Instruction::Addx(1),
-                    Instruction::Noop,
-                    Instruction::Addx(17),
-                    Instruction::Addx(-9),
-                    Instruction::Addx(1),
-                    Instruction::Addx(1),
-                    Instruction::Addx(-3),
-                    Instruction::Addx(11),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(1),
-                    Instruction::Noop,
-                    Instruction::Addx(1),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(-13),
-                    Instruction::Addx(-19),
-                    Instruction::Addx(1),
-                    Instruction::Addx(3),
-                    Instruction::Addx(26),
-                    Instruction::Addx(-30),
-                    Instruction::Addx(12),
-                    Instruction::Addx(-1),
-                    Instruction::Addx(3),
-                    Instruction::Addx(1),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(-9),
-                    Instruction::Addx(18),
-                    Instruction::Addx(1),
-                    Instruction::Addx(2),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(9),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(-1),
-                    Instruction::Addx(2),
-                    Instruction::Addx(-37),
-                    Instruction::Addx(1),
-                    Instruction::Addx(3),
-                    Instruction::Noop,
-                    Instruction::Addx(15),
-                    Instruction::Addx(-21),
-                    Instruction::Addx(22),
-                    Instruction::Addx(-6),
-                    Instruction::Addx(1),
-                    Instruction::Noop,
-                    Instruction::Addx(2),
-                    Instruction::Addx(1),
-                    Instruction::Noop,
-                    Instruction::Addx(-10),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Addx(20),
-                    Instruction::Addx(1),
-                    Instruction::Addx(2),
-                    Instruction::Addx(2),
-                    Instruction::Addx(-6),
-                    Instruction::Addx(-11),
-                    Instruction::Noop,
-                    Instruction::Noop,
-                    Instruction::Noop,
+                    addx(15),
+                    addx(-11),
+                    addx(6),
+                    addx(-3),
+                    addx(5),
+                    addx(-1),
+                    addx(-8),
+                    addx(13),
+                    addx(4),
+                    noop(),
+                    addx(-1),
+                    addx(5),
+                    addx(-1),
+                    addx(5),
+                    addx(-1),
+                    addx(5),
+                    addx(-1),
+                    addx(5),
+                    addx(-1),
+                    addx(-35),
+                    addx(1),
+                    addx(24),
+                    addx(-19),
+                    addx(1),
+                    addx(16),
+                    addx(-11),
+                    noop(),
+                    noop(),
+                    addx(21),
+                    addx(-15),
+                    noop(),
+                    noop(),
+                    addx(-3),
+                    addx(9),
+                    addx(1),
+                    addx(-3),
+                    addx(8),
+                    addx(1),
+                    addx(5),
+                    noop(),
+                    noop(),
+                    noop(),
+                    noop(),
+                    noop(),
+                    addx(-36),
+                    noop(),
+                    addx(1),
+                    addx(7),
+                    noop(),
+                    noop(),
+                    noop(),
+                    addx(2),
+                    addx(6),
+                    noop(),
+                    noop(),
+                    noop(),
+                    noop(),
+                    noop(),
+                    addx(1),
+                    noop(),
+                    noop(),
+                    addx(7),
+                    addx(1),
+                    noop(),
+                    addx(-13),
+                    addx(13),
+                    addx(7),
+                    noop(),
+                    addx(1),
+                    addx(-33),
+                    noop(),
+                    noop(),
+                    noop(),
+                    addx(2),
+                    noop(),
+                    noop(),
+                    noop(),
+                    addx(8),
+                    noop(),
+                    addx(-1),
+                    addx(2),
+                    addx(1),
+                    noop(),
+                    addx(17),
+                    addx(-9),
+                    addx(1),
+                    addx(1),
+                    addx(-3),
+                    addx(11),
+                    noop(),
+                    noop(),
+                    addx(1),
+                    noop(),
+                    addx(1),
+                    noop(),
+                    noop(),
+                    addx(-13),
+                    addx(-19),
+                    addx(1),
+                    addx(3),
+                    addx(26),
+                    addx(-30),
+                    addx(12),
+                    addx(-1),
+                    addx(3),
+                    addx(1),
+                    noop(),
+                    noop(),
+                    noop(),
+                    addx(-9),
+                    addx(18),
+                    addx(1),
+                    addx(2),
+                    noop(),
+                    noop(),
+                    addx(9),
+                    noop(),
+                    noop(),
+                    noop(),
+                    addx(-1),
+                    addx(2),
+                    addx(-37),
+                    addx(1),
+                    addx(3),
+                    noop(),
+                    addx(15),
+                    addx(-21),
+                    addx(22),
+                    addx(-6),
+                    addx(1),
+                    noop(),
+                    addx(2),
+                    addx(1),
+                    noop(),
+                    addx(-10),
+                    noop(),
+                    noop(),
+                    addx(20),
+                    addx(1),
+                    addx(2),
+                    addx(2),
+                    addx(-6),
+                    addx(-11),
+                    noop(),
+                    noop(),
+                    noop(),
                 ],
             },
         )
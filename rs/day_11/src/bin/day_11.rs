@@ -1,9 +1,27 @@
-use day_11::{read_data, Input};
+use clap::Parser;
+use day_11::{read_data, Input, Relief};
+
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        long,
+        value_name = "ROUNDS",
+        help = "Also print the monkey business after ROUNDS rounds without relief"
+    )]
+    rounds: Option<usize>,
+}
 
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
+
+    if let Some(rounds) = opts.rounds {
+        let monkey_business = input.monkey_business(rounds, Relief::Modulo).unwrap();
+        println!("After {rounds} rounds: {monkey_business}");
+    }
 }
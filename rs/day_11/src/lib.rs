@@ -1,4 +1,10 @@
-use std::{collections::VecDeque, fs::read_to_string, path::Path};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    fs::read_to_string,
+    num::NonZeroUsize,
+    path::Path,
+};
 
 pub const PART_1: usize = 64032;
 pub const PART_2: usize = 12729522272;
@@ -10,190 +16,639 @@ pub fn read_data(data_dir: &str) -> String {
         .to_string()
 }
 
-#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct Input {
     monkeys: Vec<Monkey>,
 }
 
 impl Input {
-    pub fn from_data(data: &str) -> Self {
-        let monkeys = data.split("\n\n").map(Monkey::from).collect();
-        Input { monkeys }
-    }
+    pub fn from_data(data: &str) -> Result<Self, Error> {
+        let mut lines = (1..).zip(data.lines()).peekable();
 
-    pub fn part_1(&self) -> usize {
-        let mut inspection_counts = vec![0; self.monkeys.len()];
+        let mut monkeys = Vec::new();
+        let mut target_lines = Vec::new();
+        while lines.peek().is_some() {
+            let (monkey, lines_naming_targets) = Monkey::parse(monkeys.len(), &mut lines)?;
+            monkeys.push(monkey);
+            target_lines.push(lines_naming_targets);
+            while lines.next_if(|(_, line)| line.trim().is_empty()).is_some() {}
+        }
 
-        let mut items: Vec<(usize, usize)> = self
-            .monkeys
-            .iter()
-            .enumerate()
-            .flat_map(|(idx, monkey)| monkey.items.iter().map(move |&item| (item, idx)))
-            .collect();
-
-        for _round in 0..20 {
-            for (turn, (monkey, count)) in self
-                .monkeys
-                .iter()
-                .zip(inspection_counts.iter_mut())
-                .enumerate()
+        for (monkey, lines) in monkeys.iter().zip(target_lines) {
+            if let Some((line, &target)) = lines
+                .into_iter()
+                .zip(&monkey.targets)
+                .find(|(_, &target)| target >= monkeys.len())
             {
-                for (item, owner) in items.iter_mut() {
-                    if *owner != turn {
-                        continue;
-                    }
-                    *count += 1;
-
-                    *item = match monkey.operation {
-                        (Operator::Add, None) => *item + *item,
-                        (Operator::Add, Some(val)) => *item + val,
-                        (Operator::Mul, None) => *item * *item,
-                        (Operator::Mul, Some(val)) => *item * val,
-                    } / 3;
-
-                    *owner = if *item % monkey.test[0] == 0 {
-                        monkey.test[1]
-                    } else {
-                        monkey.test[2]
-                    };
-                }
+                return Err(Error::UnknownMonkey {
+                    line,
+                    monkey: target,
+                });
             }
         }
 
-        inspection_counts.sort();
-        inspection_counts.iter().rev().take(2).product()
+        Ok(Self { monkeys })
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.monkey_business(20, Relief::Divide(NonZeroUsize::new(3).unwrap()))
+            .unwrap()
     }
 
     pub fn part_2(&self) -> usize {
-        let modulo: usize = self.monkeys.iter().map(|monkey| monkey.test[0]).product();
+        self.monkey_business(10_000, Relief::Modulo).unwrap()
+    }
 
-        let mut inspection_counts = vec![0; self.monkeys.len()];
+    pub fn simulate(&self, relief: Relief) -> Result<Simulation<'_>, Error> {
+        Simulation::new(&self.monkeys, relief)
+    }
+
+    // Returns how many items each monkey inspected over the given rounds.
+    pub fn inspections(&self, rounds: usize, relief: Relief) -> Result<Vec<usize>, Error> {
+        let mut simulation = self.simulate(relief)?;
+        for _ in 0..rounds {
+            simulation.play_round()?;
+        }
+        Ok(simulation.inspections)
+    }
 
-        let mut items: Vec<(usize, usize)> = self
-            .monkeys
+    // Counts the same inspections as `inspections`, but follows each item
+    // until its state repeats, so the cost stops growing with the number of
+    // rounds once every item is in a cycle.
+    pub fn inspections_after(&self, rounds: usize, relief: Relief) -> Result<Vec<usize>, Error> {
+        let rules = Rules::new(&self.monkeys, relief)?;
+        let mut inspections = vec![0usize; self.monkeys.len()];
+        for item in rules.starting_items() {
+            let counts = rules.item_inspections(item, rounds)?;
+            for (monkey, (total, count)) in inspections.iter_mut().zip(counts).enumerate() {
+                *total = total
+                    .checked_add(count)
+                    .ok_or(Error::InspectionOverflow { monkey })?;
+            }
+        }
+        Ok(inspections)
+    }
+
+    pub fn monkey_business(&self, rounds: usize, relief: Relief) -> Result<usize, Error> {
+        let mut inspections = self.inspections_after(rounds, relief)?;
+        inspections.sort_unstable_by(|a, b| b.cmp(a));
+        inspections
             .iter()
-            .enumerate()
-            .flat_map(|(idx, monkey)| monkey.items.iter().map(move |&item| (item, idx)))
-            .collect();
-
-        for _round in 0..10_000 {
-            for (turn, (monkey, count)) in self
-                .monkeys
-                .iter()
-                .zip(inspection_counts.iter_mut())
-                .enumerate()
-            {
-                for (item, owner) in items.iter_mut() {
-                    if *owner != turn {
-                        continue;
-                    }
-                    *count += 1;
-
-                    *item = match monkey.operation {
-                        (Operator::Add, None) => *item + *item,
-                        (Operator::Add, Some(val)) => *item + val,
-                        (Operator::Mul, None) => *item * *item,
-                        (Operator::Mul, Some(val)) => *item * val,
-                    } % modulo;
-
-                    *owner = if *item % monkey.test[0] == 0 {
-                        monkey.test[1]
-                    } else {
-                        monkey.test[2]
-                    };
+            .take(2)
+            .try_fold(1usize, |product, &count| product.checked_mul(count))
+            .ok_or(Error::MonkeyBusinessOverflow)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relief {
+    // Worry levels are divided by the factor after every inspection.
+    Divide(NonZeroUsize),
+    // Worry levels are kept modulo the lowest common multiple of the monkeys'
+    // divisors, which leaves every test's outcome unchanged. This only holds
+    // when every test checks divisibility and no operation divides.
+    Modulo,
+}
+
+// How worry levels change as the monkeys play, shared by every way of playing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rules<'i> {
+    monkeys: &'i [Monkey],
+    relief: Relief,
+    modulo: usize,
+}
+
+impl<'i> Rules<'i> {
+    fn new(monkeys: &'i [Monkey], relief: Relief) -> Result<Self, Error> {
+        let mut modulo = 1;
+        if relief == Relief::Modulo {
+            for (idx, monkey) in monkeys.iter().enumerate() {
+                if !monkey.operation.is_modular() {
+                    return Err(Error::NonModularOperation { monkey: idx });
                 }
+                let Test::DivisibleBy(divisor) = monkey.test else {
+                    return Err(Error::NonModularTest { monkey: idx });
+                };
+                modulo = lcm(modulo, divisor).ok_or(Error::ModulusOverflow)?;
             }
         }
 
-        inspection_counts.sort();
-        inspection_counts.iter().rev().take(2).product()
+        Ok(Self {
+            monkeys,
+            relief,
+            modulo,
+        })
+    }
+
+    fn starting_items(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.monkeys
+            .iter()
+            .enumerate()
+            .flat_map(move |(idx, monkey)| {
+                monkey.items.iter().map(move |&item| match self.relief {
+                    Relief::Divide(_) => (idx, item),
+                    Relief::Modulo => (idx, item % self.modulo),
+                })
+            })
+    }
+
+    // Returns the monkey the item is thrown to and its new worry level.
+    fn throw(&self, turn: usize, item: usize) -> Result<(usize, usize), Error> {
+        let monkey = &self.monkeys[turn];
+        let item = match self.relief {
+            Relief::Divide(factor) => {
+                monkey.operation.evaluate(item).ok_or(Error::Overflow {
+                    monkey: turn,
+                    worry: item,
+                })? / factor.get()
+            }
+            Relief::Modulo => monkey
+                .operation
+                .evaluate_modulo(item, self.modulo)
+                .ok_or(Error::NonModularOperation { monkey: turn })?,
+        };
+        Ok((monkey.target(item), item))
+    }
+
+    // Follows a single item through a round, starting with the monkey holding
+    // it, and returns who holds it afterwards. Items thrown to a monkey that
+    // has yet to take its turn are inspected again in the same round.
+    fn play_item(
+        &self,
+        (mut turn, mut item): (usize, usize),
+        inspections: &mut [usize],
+    ) -> Result<(usize, usize), Error> {
+        loop {
+            inspections[turn] += 1;
+            let (target, worry) = self.throw(turn, item)?;
+            if target <= turn {
+                return Ok((target, worry));
+            }
+            (turn, item) = (target, worry);
+        }
+    }
+
+    // Items never affect each other, so each item's inspections are counted
+    // separately. Its state at the start of a round is finite under modular
+    // relief, so it eventually repeats, and the rounds after that only repeat
+    // the cycle's inspections.
+    fn item_inspections(&self, start: (usize, usize), rounds: usize) -> Result<Vec<usize>, Error> {
+        let mut seen = HashMap::from([(start, 0)]);
+        // The inspections made by each monkey over the first n rounds.
+        let mut totals = vec![vec![0; self.monkeys.len()]];
+
+        let mut state = start;
+        for round in 1..=rounds {
+            let mut inspections = totals[round - 1].clone();
+            state = self.play_item(state, &mut inspections)?;
+            totals.push(inspections);
+
+            if let Some(cycle_start) = seen.insert(state, round) {
+                let cycle_length = round - cycle_start;
+                let cycles = (rounds - cycle_start) / cycle_length;
+                let remainder = (rounds - cycle_start) % cycle_length;
+
+                let (before, cycle, tail) = (
+                    &totals[cycle_start],
+                    &totals[round],
+                    &totals[cycle_start + remainder],
+                );
+                return (0..self.monkeys.len())
+                    .map(|monkey| {
+                        cycles
+                            .checked_mul(cycle[monkey] - before[monkey])
+                            .and_then(|repeats| repeats.checked_add(tail[monkey]))
+                            .ok_or(Error::InspectionOverflow { monkey })
+                    })
+                    .collect();
+            }
+        }
+
+        Ok(totals.pop().unwrap())
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
-struct Monkey {
-    items: VecDeque<usize>,
-    operation: (Operator, Option<usize>),
-    test: [usize; 3],
+// Plays rounds of keep away, yielding each monkey's total inspection count
+// after every round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation<'i> {
+    rules: Rules<'i>,
+    queues: Vec<VecDeque<usize>>,
+    inspections: Vec<usize>,
+    round: usize,
+    failed: bool,
 }
 
-impl From<&str> for Monkey {
-    fn from(input: &str) -> Self {
-        let mut lines = input.lines().skip(1);
+impl<'i> Simulation<'i> {
+    fn new(monkeys: &'i [Monkey], relief: Relief) -> Result<Self, Error> {
+        let rules = Rules::new(monkeys, relief)?;
+        let mut queues = vec![VecDeque::new(); monkeys.len()];
+        for (idx, item) in rules.starting_items() {
+            queues[idx].push_back(item);
+        }
 
-        let starting_items = lines
-            .next()
-            .unwrap()
-            .split(": ")
-            .nth(1)
-            .unwrap()
-            .split(", ")
-            .map(|item| item.parse().unwrap())
-            .collect();
+        Ok(Self {
+            rules,
+            queues,
+            inspections: vec![0; monkeys.len()],
+            round: 0,
+            failed: false,
+        })
+    }
 
-        let (operator, modifier) = lines
-            .next()
-            .unwrap()
-            .split("new = old ")
-            .nth(1)
-            .unwrap()
-            .split_once(' ')
-            .unwrap();
+    pub fn round(&self) -> usize {
+        self.round
+    }
 
-        let operator = match operator {
-            "+" => Operator::Add,
-            "*" => Operator::Mul,
-            op => panic!("unexpected op: '{op}'"),
-        };
+    pub fn inspections(&self) -> &[usize] {
+        &self.inspections
+    }
+
+    // The worry levels of the items each monkey is holding.
+    pub fn items(&self) -> &[VecDeque<usize>] {
+        &self.queues
+    }
+
+    pub fn play_round(&mut self) -> Result<(), Error> {
+        for turn in 0..self.queues.len() {
+            let items = std::mem::take(&mut self.queues[turn]);
+            self.inspections[turn] += items.len();
+
+            for item in items {
+                let (target, item) = self.rules.throw(turn, item)?;
+                self.queues[target].push_back(item);
+            }
+        }
+        self.round += 1;
+        Ok(())
+    }
+}
+
+impl Iterator for Simulation<'_> {
+    type Item = Result<Vec<usize>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.play_round().map(|_| self.inspections.clone());
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> Option<usize> {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    (a / x).checked_mul(b)
+}
 
-        let rhs = match modifier {
-            "old" => None,
-            num => Some(num.parse().unwrap()),
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Monkey {
+    items: VecDeque<usize>,
+    operation: Expression,
+    test: Test,
+    // The monkeys thrown to when the test passes and fails.
+    targets: [usize; 2],
+}
+
+impl Monkey {
+    // Parses the monkey at `idx`, also returning the lines naming its targets.
+    fn parse<'d>(
+        idx: usize,
+        lines: &mut impl Iterator<Item = (usize, &'d str)>,
+    ) -> Result<(Self, [usize; 2]), Error> {
+        let mut field = |prefix: &str| {
+            let (line, text) = lines.next().ok_or(Error::MissingLine {
+                monkey: idx,
+                expected: prefix.to_string(),
+            })?;
+            text.trim()
+                .strip_prefix(prefix)
+                .map(|value| (line, value.trim()))
+                .ok_or_else(|| Error::UnexpectedLine {
+                    line,
+                    text: text.to_string(),
+                })
         };
 
-        let operation = (operator, rhs);
+        let (line, header) = field("Monkey")?;
+        if header.strip_suffix(':').and_then(|id| id.parse().ok()) != Some(idx) {
+            return Err(Error::UnexpectedLine {
+                line,
+                text: format!("Monkey {header}"),
+            });
+        }
 
-        let divisible_by = lines
-            .next()
-            .unwrap()
-            .rsplit(' ')
-            .next()
-            .unwrap()
-            .parse()
-            .unwrap();
-        let true_monkey = lines
-            .next()
-            .unwrap()
-            .rsplit(' ')
-            .next()
-            .unwrap()
-            .parse()
-            .unwrap();
-        let false_monkey = lines
-            .next()
-            .unwrap()
-            .rsplit(' ')
-            .next()
-            .unwrap()
-            .parse()
-            .unwrap();
+        let (line, items) = field("Starting items:")?;
+        let items = items
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                item.parse().map_err(|_| Error::InvalidItem {
+                    line,
+                    item: item.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let (line, operation) = field("Operation: new =")?;
+        let operation = Expression::parse(operation).ok_or_else(|| Error::InvalidExpression {
+            line,
+            expression: operation.to_string(),
+        })?;
+
+        let (line, test) = field("Test:")?;
+        let test = Test::parse(test).ok_or_else(|| Error::InvalidTest {
+            line,
+            test: test.to_string(),
+        })?;
 
-        let test = [divisible_by, true_monkey, false_monkey];
+        let mut target = |prefix: &str| {
+            let (line, target) = field(prefix)?;
+            target
+                .parse()
+                .map(|target| (line, target))
+                .map_err(|_| Error::UnexpectedLine {
+                    line,
+                    text: format!("{prefix} {target}"),
+                })
+        };
+        let (true_line, if_true) = target("If true: throw to monkey")?;
+        let (false_line, if_false) = target("If false: throw to monkey")?;
 
-        Monkey {
-            items: starting_items,
+        let monkey = Monkey {
+            items,
             operation,
             test,
-        }
+            targets: [if_true, if_false],
+        };
+        Ok((monkey, [true_line, false_line]))
+    }
+
+    fn target(&self, item: usize) -> usize {
+        self.targets[usize::from(!self.test.passes(item))]
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-enum Operator {
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operator {
     Add,
+    Sub,
     Mul,
+    Div,
+}
+
+// An operation on the worry level `old`, supporting `+`, `-`, `*` and `/` with
+// the usual precedence, and parentheses.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Expression {
+    Old,
+    Constant(usize),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut tokens = Token::tokenize(text)?.into_iter().peekable();
+        let expression = Self::parse_sum(&mut tokens)?;
+        tokens.next().is_none().then_some(expression)
+    }
+
+    fn parse_sum(tokens: &mut Tokens) -> Option<Self> {
+        let mut lhs = Self::parse_product(tokens)?;
+        while let Some(Token::Operator(operator @ (Operator::Add | Operator::Sub))) =
+            tokens.next_if(|token| matches!(token, Token::Operator(Operator::Add | Operator::Sub)))
+        {
+            lhs = Self::Binary(
+                Box::new(lhs),
+                operator,
+                Box::new(Self::parse_product(tokens)?),
+            );
+        }
+        Some(lhs)
+    }
+
+    fn parse_product(tokens: &mut Tokens) -> Option<Self> {
+        let mut lhs = Self::parse_term(tokens)?;
+        while let Some(Token::Operator(operator @ (Operator::Mul | Operator::Div))) =
+            tokens.next_if(|token| matches!(token, Token::Operator(Operator::Mul | Operator::Div)))
+        {
+            lhs = Self::Binary(Box::new(lhs), operator, Box::new(Self::parse_term(tokens)?));
+        }
+        Some(lhs)
+    }
+
+    fn parse_term(tokens: &mut Tokens) -> Option<Self> {
+        match tokens.next()? {
+            Token::Old => Some(Self::Old),
+            Token::Number(value) => Some(Self::Constant(value)),
+            Token::Open => {
+                let expression = Self::parse_sum(tokens)?;
+                (tokens.next()? == Token::Close).then_some(expression)
+            }
+            Token::Close | Token::Operator(_) => None,
+        }
+    }
+
+    // Returns `None` if the result overflows, goes negative, or divides by
+    // zero.
+    pub fn evaluate(&self, old: usize) -> Option<usize> {
+        match self {
+            Self::Old => Some(old),
+            Self::Constant(value) => Some(*value),
+            Self::Binary(lhs, operator, rhs) => {
+                let (lhs, rhs) = (lhs.evaluate(old)?, rhs.evaluate(old)?);
+                match operator {
+                    Operator::Add => lhs.checked_add(rhs),
+                    Operator::Sub => lhs.checked_sub(rhs),
+                    Operator::Mul => lhs.checked_mul(rhs),
+                    Operator::Div => lhs.checked_div(rhs),
+                }
+            }
+        }
+    }
+
+    // Division doesn't commute with taking the remainder, and a remainder
+    // can't tell whether subtracting would have gone negative, so only
+    // expressions without either can be evaluated modulo some number.
+    pub fn is_modular(&self) -> bool {
+        match self {
+            Self::Old | Self::Constant(_) => true,
+            Self::Binary(lhs, operator, rhs) => {
+                matches!(operator, Operator::Add | Operator::Mul)
+                    && lhs.is_modular()
+                    && rhs.is_modular()
+            }
+        }
+    }
+
+    // Evaluates the expression on `old` modulo `modulo`, or returns `None` if
+    // it isn't modular.
+    pub fn evaluate_modulo(&self, old: usize, modulo: usize) -> Option<usize> {
+        let wide = modulo as u128;
+        let result = match self {
+            Self::Old => old as u128 % wide,
+            Self::Constant(value) => *value as u128 % wide,
+            Self::Binary(lhs, operator, rhs) => {
+                let lhs = lhs.evaluate_modulo(old, modulo)? as u128;
+                let rhs = rhs.evaluate_modulo(old, modulo)? as u128;
+                match operator {
+                    Operator::Add => (lhs + rhs) % wide,
+                    Operator::Mul => (lhs * rhs) % wide,
+                    Operator::Sub | Operator::Div => return None,
+                }
+            }
+        };
+        Some(result as usize)
+    }
+}
+
+type Tokens = std::iter::Peekable<std::vec::IntoIter<Token>>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Token {
+    Old,
+    Number(usize),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+impl Token {
+    fn tokenize(text: &str) -> Option<Vec<Self>> {
+        let mut tokens = Vec::new();
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, char)) = chars.next() {
+            let token = match char {
+                '+' => Token::Operator(Operator::Add),
+                '-' => Token::Operator(Operator::Sub),
+                '*' => Token::Operator(Operator::Mul),
+                '/' => Token::Operator(Operator::Div),
+                '(' => Token::Open,
+                ')' => Token::Close,
+                char if char.is_whitespace() => continue,
+                char if char.is_ascii_alphanumeric() => {
+                    let mut end = start + 1;
+                    while let Some((idx, _)) =
+                        chars.next_if(|(_, char)| char.is_ascii_alphanumeric())
+                    {
+                        end = idx + 1;
+                    }
+                    match &text[start..end] {
+                        "old" => Token::Old,
+                        number => Token::Number(number.parse().ok()?),
+                    }
+                }
+                _ => return None,
+            };
+            tokens.push(token);
+        }
+        Some(tokens)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Test {
+    DivisibleBy(usize),
+    GreaterThan(usize),
+    LessThan(usize),
+    EqualTo(usize),
+}
+
+impl Test {
+    pub fn parse(text: &str) -> Option<Self> {
+        let (predicate, value) = text.trim().rsplit_once(' ')?;
+        let value = value.parse().ok()?;
+        match predicate.trim() {
+            "divisible by" if value > 0 => Some(Self::DivisibleBy(value)),
+            "greater than" => Some(Self::GreaterThan(value)),
+            "less than" => Some(Self::LessThan(value)),
+            "equal to" => Some(Self::EqualTo(value)),
+            _ => None,
+        }
+    }
+
+    pub fn passes(&self, worry: usize) -> bool {
+        match *self {
+            Self::DivisibleBy(divisor) => worry.is_multiple_of(divisor),
+            Self::GreaterThan(value) => worry > value,
+            Self::LessThan(value) => worry < value,
+            Self::EqualTo(value) => worry == value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    MissingLine { monkey: usize, expected: String },
+    UnexpectedLine { line: usize, text: String },
+    InvalidItem { line: usize, item: String },
+    InvalidExpression { line: usize, expression: String },
+    InvalidTest { line: usize, test: String },
+    UnknownMonkey { line: usize, monkey: usize },
+    NonModularOperation { monkey: usize },
+    NonModularTest { monkey: usize },
+    ModulusOverflow,
+    Overflow { monkey: usize, worry: usize },
+    InspectionOverflow { monkey: usize },
+    MonkeyBusinessOverflow,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MissingLine { monkey, expected } => {
+                write!(f, "monkey {monkey}: missing {expected:?} line")
+            }
+            Error::UnexpectedLine { line, text } => {
+                write!(f, "line {line}: unexpected line {text:?}")
+            }
+            Error::InvalidItem { line, item } => {
+                write!(f, "line {line}: invalid item {item:?}")
+            }
+            Error::InvalidExpression { line, expression } => {
+                write!(f, "line {line}: invalid expression {expression:?}")
+            }
+            Error::InvalidTest { line, test } => {
+                write!(f, "line {line}: invalid test {test:?}")
+            }
+            Error::UnknownMonkey { line, monkey } => {
+                write!(f, "line {line}: unknown monkey {monkey}")
+            }
+            Error::NonModularOperation { monkey } => write!(
+                f,
+                "monkey {monkey}: operation divides or subtracts, so worry levels can't be reduced"
+            ),
+            Error::NonModularTest { monkey } => write!(
+                f,
+                "monkey {monkey}: test doesn't check divisibility, so worry levels can't be reduced"
+            ),
+            Error::ModulusOverflow => {
+                f.write_str("the monkeys' divisors have no common multiple that fits in a usize")
+            }
+            Error::Overflow { monkey, worry } => write!(
+                f,
+                "monkey {monkey}: operation on worry level {worry} is out of range"
+            ),
+            Error::InspectionOverflow { monkey } => {
+                write!(
+                    f,
+                    "monkey {monkey}: too many inspections to count in a usize"
+                )
+            }
+            Error::MonkeyBusinessOverflow => {
+                f.write_str("the level of monkey business doesn't fit in a usize")
+            }
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,14 +660,100 @@ mod tests {
 
         struct Case<'c> {
             input: &'c str,
-            expected: Input,
+            expected: Result<Input, Error>,
         }
 
         #[test]
         fn example() {
             run(&Case {
                 input: super::example().0,
-                expected: super::example().1,
+                expected: Ok(super::example().1),
+            })
+        }
+
+        #[test]
+        fn richer_expressions() {
+            run(&Case {
+                input: "Monkey 0:
+  Starting items:
+  Operation: new = (old - 2) * (old + 1) * 3
+  Test: greater than 100
+    If true: throw to monkey 0
+    If false: throw to monkey 0",
+                expected: Ok(Input {
+                    monkeys: vec![Monkey {
+                        items: VecDeque::new(),
+                        operation: binary(
+                            binary(
+                                binary(Expression::Old, Operator::Sub, Expression::Constant(2)),
+                                Operator::Mul,
+                                binary(Expression::Old, Operator::Add, Expression::Constant(1)),
+                            ),
+                            Operator::Mul,
+                            Expression::Constant(3),
+                        ),
+                        test: Test::GreaterThan(100),
+                        targets: [0, 0],
+                    }],
+                }),
+            })
+        }
+
+        #[test]
+        fn invalid_expression() {
+            run(&Case {
+                input: "Monkey 0:
+  Starting items: 1
+  Operation: new = old * (old + 1
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 0",
+                expected: Err(Error::InvalidExpression {
+                    line: 3,
+                    expression: "old * (old + 1".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn invalid_test() {
+            run(&Case {
+                input: "Monkey 0:
+  Starting items: 1
+  Operation: new = old
+  Test: divisible by 0
+    If true: throw to monkey 0
+    If false: throw to monkey 0",
+                expected: Err(Error::InvalidTest {
+                    line: 4,
+                    test: "divisible by 0".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn unknown_monkey() {
+            run(&Case {
+                input: "Monkey 0:
+  Starting items: 1
+  Operation: new = old
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 1",
+                expected: Err(Error::UnknownMonkey { line: 6, monkey: 1 }),
+            })
+        }
+
+        #[test]
+        fn missing_line() {
+            run(&Case {
+                input: "Monkey 0:
+  Starting items: 1
+  Operation: new = old",
+                expected: Err(Error::MissingLine {
+                    monkey: 0,
+                    expected: "Test:".to_string(),
+                }),
             })
         }
 
@@ -221,6 +762,346 @@ mod tests {
         }
     }
 
+    mod expression {
+        use super::*;
+
+        struct Case {
+            expression: &'static str,
+            old: usize,
+            expected: Option<usize>,
+        }
+
+        #[test]
+        fn precedence() {
+            run(&Case {
+                expression: "old + 2 * old - 3",
+                old: 5,
+                expected: Some(12),
+            })
+        }
+
+        #[test]
+        fn parentheses() {
+            run(&Case {
+                expression: "(old + 2) * (old - 3)",
+                old: 5,
+                expected: Some(14),
+            })
+        }
+
+        #[test]
+        fn negative() {
+            run(&Case {
+                expression: "old - 6",
+                old: 5,
+                expected: None,
+            })
+        }
+
+        #[test]
+        fn overflow() {
+            run(&Case {
+                expression: "old * old",
+                old: usize::MAX,
+                expected: None,
+            })
+        }
+
+        #[test]
+        fn division_by_zero() {
+            run(&Case {
+                expression: "old / (old - 5)",
+                old: 5,
+                expected: None,
+            })
+        }
+
+        fn run(test: &Case) {
+            let expression = Expression::parse(test.expression).unwrap();
+            assert_eq!(test.expected, expression.evaluate(test.old));
+        }
+
+        #[test]
+        fn modulo() {
+            let expression = Expression::parse("(old + 7) * old + 4").unwrap();
+            assert_eq!(Some(372 % 13), expression.evaluate_modulo(16, 13));
+            assert_eq!(
+                None,
+                Expression::parse("old / 2")
+                    .unwrap()
+                    .evaluate_modulo(16, 13)
+            );
+        }
+
+        #[test]
+        fn modulo_subtraction() {
+            // 5 - 6 goes negative, even though 18 - 6 wouldn't and 18 is 5
+            // modulo 13, so neither evaluator can answer.
+            let expression = Expression::parse("old - 6").unwrap();
+            assert!(!expression.is_modular());
+            assert_eq!(None, expression.evaluate(5));
+            assert_eq!(None, expression.evaluate_modulo(5, 13));
+            assert_eq!(None, expression.evaluate_modulo(18, 13));
+        }
+
+        #[test]
+        fn invalid() {
+            for text in ["", "old +", "(old", "old)", "old old", "old % 2", "new"] {
+                assert_eq!(None, Expression::parse(text), "{text:?}");
+            }
+        }
+    }
+
+    mod inspections {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            rounds: usize,
+            relief: Relief,
+            expected: Result<Vec<usize>, Error>,
+        }
+
+        #[test]
+        fn example_with_relief() {
+            run(&Case {
+                data: super::example().1,
+                rounds: 20,
+                relief: Relief::Divide(NonZeroUsize::new(3).unwrap()),
+                expected: Ok(vec![101, 95, 7, 105]),
+            })
+        }
+
+        #[test]
+        fn example_one_round() {
+            run(&Case {
+                data: super::example().1,
+                rounds: 1,
+                relief: Relief::Modulo,
+                expected: Ok(vec![2, 4, 3, 6]),
+            })
+        }
+
+        #[test]
+        fn example_one_round_without_relief() {
+            run(&Case {
+                data: super::example().1,
+                rounds: 1,
+                relief: Relief::Divide(NonZeroUsize::MIN),
+                expected: Ok(vec![2, 4, 3, 6]),
+            })
+        }
+
+        #[test]
+        fn example_thousand_rounds() {
+            run(&Case {
+                data: super::example().1,
+                rounds: 1000,
+                relief: Relief::Modulo,
+                expected: Ok(vec![5204, 4792, 199, 5192]),
+            })
+        }
+
+        #[test]
+        fn overflow() {
+            run(&Case {
+                data: super::example().1,
+                rounds: 20,
+                relief: Relief::Divide(NonZeroUsize::MIN),
+                expected: Err(Error::Overflow {
+                    monkey: 0,
+                    worry: 13_988_703_546_165_100_909,
+                }),
+            })
+        }
+
+        #[test]
+        fn non_modular_operation() {
+            run(&Case {
+                data: monkeys("old / 2", "divisible by 2"),
+                rounds: 1,
+                relief: Relief::Modulo,
+                expected: Err(Error::NonModularOperation { monkey: 1 }),
+            })
+        }
+
+        #[test]
+        fn non_modular_subtraction() {
+            run(&Case {
+                data: monkeys("old * 2 - 1", "divisible by 2"),
+                rounds: 1,
+                relief: Relief::Modulo,
+                expected: Err(Error::NonModularOperation { monkey: 1 }),
+            })
+        }
+
+        #[test]
+        fn non_modular_test() {
+            run(&Case {
+                data: monkeys("old * 2", "less than 10"),
+                rounds: 1,
+                relief: Relief::Modulo,
+                expected: Err(Error::NonModularTest { monkey: 1 }),
+            })
+        }
+
+        #[test]
+        fn other_predicates() {
+            run(&Case {
+                data: monkeys("old * 2 - 1", "less than 10"),
+                rounds: 3,
+                relief: Relief::Divide(NonZeroUsize::MIN),
+                expected: Ok(vec![1, 3]),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                test.data.inspections(test.rounds, test.relief)
+            );
+        }
+
+        // Monkey 0 passes its item to monkey 1, which keeps it while the test
+        // passes, inspecting it again on its next turn.
+        fn monkeys(operation: &str, test: &str) -> Input {
+            Input::from_data(&format!(
+                "Monkey 0:
+  Starting items: 3
+  Operation: new = old
+  Test: divisible by 3
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items:
+  Operation: new = {operation}
+  Test: {test}
+    If true: throw to monkey 1
+    If false: throw to monkey 0"
+            ))
+            .unwrap()
+        }
+    }
+
+    mod inspections_after {
+        use super::*;
+
+        struct Case {
+            rounds: usize,
+            relief: Relief,
+            expected: Result<Vec<usize>, Error>,
+        }
+
+        #[test]
+        fn example_with_relief() {
+            run(&Case {
+                rounds: 20,
+                relief: Relief::Divide(NonZeroUsize::new(3).unwrap()),
+                expected: Ok(vec![101, 95, 7, 105]),
+            })
+        }
+
+        #[test]
+        fn example_ten_thousand_rounds() {
+            run(&Case {
+                rounds: 10_000,
+                relief: Relief::Modulo,
+                expected: Ok(vec![52166, 47830, 1938, 52013]),
+            })
+        }
+
+        #[test]
+        fn example_no_rounds() {
+            run(&Case {
+                rounds: 0,
+                relief: Relief::Modulo,
+                expected: Ok(vec![0, 0, 0, 0]),
+            })
+        }
+
+        #[test]
+        fn example_billion_rounds() {
+            run(&Case {
+                rounds: 1_000_000_000,
+                relief: Relief::Modulo,
+                expected: Ok(vec![
+                    5_217_653_496,
+                    4_782_346_500,
+                    193_256_586,
+                    5_202_028_499,
+                ]),
+            })
+        }
+
+        #[test]
+        fn example_overflow() {
+            run(&Case {
+                rounds: usize::MAX,
+                relief: Relief::Modulo,
+                expected: Err(Error::InspectionOverflow { monkey: 0 }),
+            })
+        }
+
+        #[test]
+        fn matches_simulation() {
+            let (_, input) = example();
+            for rounds in [1, 7, 1234, 54_321] {
+                assert_eq!(
+                    input.inspections(rounds, Relief::Modulo),
+                    input.inspections_after(rounds, Relief::Modulo),
+                    "{rounds} rounds"
+                );
+            }
+        }
+
+        fn run(test: &Case) {
+            let (_, input) = super::example();
+            assert_eq!(
+                test.expected,
+                input.inspections_after(test.rounds, test.relief)
+            );
+        }
+    }
+
+    mod simulate {
+
+        use super::*;
+
+        #[test]
+        fn snapshots() {
+            let (_, input) = example();
+            let snapshots = input
+                .simulate(Relief::Modulo)
+                .unwrap()
+                .take(20)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(vec![2, 4, 3, 6], snapshots[0]);
+            assert_eq!(vec![99, 97, 8, 103], snapshots[19]);
+        }
+
+        #[test]
+        fn items() {
+            let (_, input) = example();
+            let mut simulation = input
+                .simulate(Relief::Divide(NonZeroUsize::new(3).unwrap()))
+                .unwrap();
+            simulation.play_round().unwrap();
+
+            assert_eq!(1, simulation.round());
+            assert_eq!(
+                [
+                    VecDeque::from([20, 23, 27, 26]),
+                    VecDeque::from([2080, 25, 167, 207, 401, 1046]),
+                    VecDeque::new(),
+                    VecDeque::new(),
+                ],
+                simulation.items()
+            );
+        }
+    }
+
     mod part_1 {
         use super::*;
 
@@ -240,7 +1121,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_1,
             })
         }
@@ -269,7 +1150,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_2,
             })
         }
@@ -279,6 +1160,10 @@ mod tests {
         }
     }
 
+    fn binary(lhs: Expression, operator: Operator, rhs: Expression) -> Expression {
+        Expression::Binary(Box::new(lhs), operator, Box::new(rhs))
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "Monkey 0:
@@ -312,23 +1197,27 @@ Monkey 3:
                 monkeys: vec![
                     Monkey {
                         items: VecDeque::from([79, 98]),
-                        operation: (Operator::Mul, Some(19)),
-                        test: [23, 2, 3],
+                        operation: binary(Expression::Old, Operator::Mul, Expression::Constant(19)),
+                        test: Test::DivisibleBy(23),
+                        targets: [2, 3],
                     },
                     Monkey {
                         items: VecDeque::from([54, 65, 75, 74]),
-                        operation: (Operator::Add, Some(6)),
-                        test: [19, 2, 0],
+                        operation: binary(Expression::Old, Operator::Add, Expression::Constant(6)),
+                        test: Test::DivisibleBy(19),
+                        targets: [2, 0],
                     },
                     Monkey {
                         items: VecDeque::from([79, 60, 97]),
-                        operation: (Operator::Mul, None),
-                        test: [13, 1, 3],
+                        operation: binary(Expression::Old, Operator::Mul, Expression::Old),
+                        test: Test::DivisibleBy(13),
+                        targets: [1, 3],
                     },
                     Monkey {
                         items: VecDeque::from([74]),
-                        operation: (Operator::Add, Some(3)),
-                        test: [17, 0, 1],
+                        operation: binary(Expression::Old, Operator::Add, Expression::Constant(3)),
+                        test: Test::DivisibleBy(17),
+                        targets: [0, 1],
                     },
                 ],
             },
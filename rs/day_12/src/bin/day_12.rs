@@ -1,9 +1,62 @@
-use day_12::{read_data, Input};
+use clap::Parser;
+use day_12::{read_data, ClimbRule, Input};
+
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(long, help = "Draw the shortest route from S to E over the map")]
+    route: bool,
+
+    #[arg(long, default_value_t = 1, help = "Furthest a single step can climb")]
+    max_ascent: u8,
+
+    #[arg(
+        long,
+        default_value_t = u8::MAX,
+        help = "Furthest a single step can descend"
+    )]
+    max_descent: u8,
+
+    #[arg(
+        long,
+        value_name = "ELEVATION",
+        value_parser = parse_elevation,
+        help = "Print the square at ELEVATION (a to z) closest to E"
+    )]
+    best_start: Option<u8>,
+}
+
+fn parse_elevation(text: &str) -> Result<u8, String> {
+    match text.as_bytes() {
+        &[elevation @ b'a'..=b'z'] => Ok(elevation - b'a'),
+        _ => Err(format!("expected a letter from a to z, got {text:?}")),
+    }
+}
 
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
     let input = Input::from_data(&contents);
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
+
+    let rule = ClimbRule {
+        max_ascent: opts.max_ascent,
+        max_descent: opts.max_descent,
+    };
+
+    if opts.route {
+        match input.shortest_path(input.start(), rule) {
+            Some(route) => println!("{} steps:\n{}", route.steps(), input.render(&route)),
+            None => println!("No route from S to E"),
+        }
+    }
+
+    if let Some(elevation) = opts.best_start {
+        match input.distances_to_end(rule).best_start(elevation) {
+            Some(((x, y), steps)) => println!("Best start: ({x}, {y}), {steps} steps from E"),
+            None => println!("No square at that elevation reaches E"),
+        }
+    }
 }
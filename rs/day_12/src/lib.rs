@@ -1,9 +1,6 @@
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap},
-    fs::read_to_string,
-    path::Path,
-};
+use std::{cmp::Ordering, collections::VecDeque, fs::read_to_string, path::Path};
+
+use grid::{Grid, Point};
 
 pub const PART_1: usize = 504;
 pub const PART_2: usize = 500;
@@ -17,137 +14,191 @@ pub fn read_data(data_dir: &str) -> String {
 
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct Input {
-    start: (usize, usize),
-    end: (usize, usize),
-    grid: Vec<Vec<u8>>,
-    nodes_to_neighbours: HashMap<(usize, usize), Vec<(usize, usize)>>,
+    start: Point,
+    end: Point,
+    heights: Grid<u8>,
 }
 
 impl Input {
     pub fn from_data(data: &str) -> Self {
-        let mut output = Self {
-            start: (0, 0),
-            end: (0, 0),
-            grid: Vec::with_capacity(data.len()),
-            nodes_to_neighbours: HashMap::with_capacity(data.len()),
-        };
-
-        for (y, line) in data.lines().enumerate() {
-            let mut row = Vec::with_capacity(line.len());
-            for (x, chr) in line.chars().enumerate() {
-                let chr = match chr {
-                    'S' => {
-                        output.start = (x, y);
-                        'a'
-                    }
-                    'E' => {
-                        output.end = (x, y);
-                        'z'
-                    }
-                    c => c,
-                };
-                let height = chr as u8 - b'a';
-
-                if !output.grid.is_empty() {
-                    let above = *output.grid.last().unwrap().get(x).unwrap();
-                    if height >= above || above - height < 2 {
-                        output
-                            .nodes_to_neighbours
-                            .entry((x, y))
-                            .and_modify(|edge_list| edge_list.push((x, y - 1)))
-                            .or_insert_with(|| vec![(x, y - 1)]);
-                    }
-                    if above >= height || height - above < 2 {
-                        output
-                            .nodes_to_neighbours
-                            .entry((x, y - 1))
-                            .and_modify(|edge_list| edge_list.push((x, y)))
-                            .or_insert_with(|| vec![(x, y)]);
-                    }
-                }
+        let cells = Grid::parse(data, Some).unwrap();
+
+        Self {
+            start: cells.position(|&cell| cell == 'S').unwrap(),
+            end: cells.position(|&cell| cell == 'E').unwrap(),
+            heights: cells.map(|&cell| match cell {
+                'S' => 0,
+                'E' => b'z' - b'a',
+                c => c as u8 - b'a',
+            }),
+        }
+    }
+
+    pub fn part_1(&self) -> usize {
+        self.shortest_path(self.start, ClimbRule::default())
+            .unwrap()
+            .steps()
+    }
+
+    pub fn part_2(&self) -> usize {
+        let (_, steps) = self
+            .distances_to_end(ClimbRule::default())
+            .best_start(0)
+            .unwrap();
+        steps
+    }
+
+    pub fn start(&self) -> Point {
+        self.start
+    }
+
+    pub fn end(&self) -> Point {
+        self.end
+    }
 
-                if !row.is_empty() {
-                    let left = *row.last().unwrap();
-                    if height >= left || left - height < 2 {
-                        output
-                            .nodes_to_neighbours
-                            .entry((x, y))
-                            .and_modify(|edge_list| edge_list.push((x - 1, y)))
-                            .or_insert_with(|| vec![(x - 1, y)]);
-                    }
-                    if left >= height || height - left < 2 {
-                        output
-                            .nodes_to_neighbours
-                            .entry((x - 1, y))
-                            .and_modify(|edge_list| edge_list.push((x, y)))
-                            .or_insert_with(|| vec![(x, y)]);
-                    }
+    fn neighbours(&self, point: Point, rule: ClimbRule) -> impl Iterator<Item = Point> + '_ {
+        let height = self.heights[point];
+        self.heights
+            .neighbours(point)
+            .filter(move |&neighbour| rule.allows(height, self.heights[neighbour]))
+    }
+
+    // Breadth-first search from `start`, returning one of the shortest routes to
+    // the end.
+    pub fn shortest_path(&self, start: Point, rule: ClimbRule) -> Option<Route> {
+        let mut previous = self.heights.map(|_| None);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(point) = queue.pop_front() {
+            if point == self.end {
+                let mut points = vec![point];
+                while let Some(&Some(before)) = previous.get(*points.last().unwrap()) {
+                    points.push(before);
                 }
+                points.reverse();
+                return Some(Route { points });
+            }
 
-                row.push(height);
+            for neighbour in self.neighbours(point, rule) {
+                if neighbour != start && previous[neighbour].is_none() {
+                    previous[neighbour] = Some(point);
+                    queue.push_back(neighbour);
+                }
             }
-            output.grid.push(row);
         }
 
-        output
+        None
     }
 
-    pub fn part_1(&self) -> usize {
-        self.shortest_path_to_destination(self.start).unwrap()
+    // Searches backwards from the end, finding the fewest steps to it from
+    // every square at once.
+    pub fn distances_to_end(&self, rule: ClimbRule) -> Distances<'_> {
+        let mut steps = self.heights.map(|_| None);
+        steps[self.end] = Some(0);
+        let mut queue = VecDeque::from([self.end]);
+
+        while let Some(point) = queue.pop_front() {
+            let next = steps[point].map(|steps| steps + 1);
+            let height = self.heights[point];
+            for neighbour in self.heights.neighbours(point) {
+                if steps[neighbour].is_none() && rule.allows(self.heights[neighbour], height) {
+                    steps[neighbour] = next;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        Distances {
+            heights: &self.heights,
+            steps,
+        }
     }
 
-    pub fn part_2(&self) -> usize {
-        self.grid
-            .iter()
-            .enumerate()
-            .flat_map(|(y, v)| {
-                v.iter()
-                    .enumerate()
-                    .filter(|(_, v)| v.eq(&&0))
-                    .map(move |(x, _)| {
-                        self.shortest_path_to_destination((x, y))
-                            .unwrap_or(usize::MAX)
-                    })
-            })
-            .min()
-            .unwrap()
+    // Draws the route over the map the way the puzzle does, with arrows showing
+    // which way it leaves each square.
+    pub fn render(&self, route: &Route) -> String {
+        let mut cells = self.heights.map(|_| '.');
+        for step in route.points.windows(2) {
+            let ((x, y), (to_x, to_y)) = (step[0], step[1]);
+            cells[(x, y)] = match (to_x.cmp(&x), to_y.cmp(&y)) {
+                (_, Ordering::Less) => '^',
+                (_, Ordering::Greater) => 'v',
+                (Ordering::Less, _) => '<',
+                _ => '>',
+            };
+        }
+        cells[self.end] = 'E';
+        cells.render(|_, &cell| cell)
     }
+}
 
-    fn shortest_path_to_destination(&self, start: (usize, usize)) -> Option<usize> {
-        let mut distances = HashMap::with_capacity(self.nodes_to_neighbours.len());
-        let mut shortest_distance_queue = BinaryHeap::with_capacity(self.nodes_to_neighbours.len());
+// Limits how far up or down a single step can go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClimbRule {
+    pub max_ascent: u8,
+    pub max_descent: u8,
+}
 
-        distances.insert(start, 0);
+// You can step down any distance, but only climb one higher.
+impl Default for ClimbRule {
+    fn default() -> Self {
+        Self {
+            max_ascent: 1,
+            max_descent: u8::MAX,
+        }
+    }
+}
 
-        for &neighbour in self.nodes_to_neighbours.get(&start).unwrap() {
-            distances.insert(neighbour, 1);
-            shortest_distance_queue.push(Reverse((1, neighbour)))
+impl ClimbRule {
+    pub fn allows(&self, from: u8, to: u8) -> bool {
+        if to >= from {
+            to - from <= self.max_ascent
+        } else {
+            from - to <= self.max_descent
         }
+    }
+}
 
-        while let Some(Reverse((distance_from_start, node))) = shortest_distance_queue.pop() {
-            if distance_from_start.ne(distances.get(&node).unwrap()) {
-                continue;
-            }
+// The fewest steps from each square to the end, if it can be reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distances<'i> {
+    heights: &'i Grid<u8>,
+    steps: Grid<Option<usize>>,
+}
 
-            if node == self.end {
-                return Some(distance_from_start);
-            }
+impl Distances<'_> {
+    pub fn steps(&self, point: Point) -> Option<usize> {
+        self.steps.get(point).copied().flatten()
+    }
 
-            let new_distance = distance_from_start + 1;
+    pub fn grid(&self) -> &Grid<Option<usize>> {
+        &self.steps
+    }
 
-            for &neighbour in self.nodes_to_neighbours.get(&node).unwrap() {
-                let existing_distance = distances.get(&neighbour).copied().unwrap_or(usize::MAX);
+    // Returns the square at the given elevation, counting up from 0 for `a`,
+    // closest to the end. Ties go to the first in reading order.
+    pub fn best_start(&self, elevation: u8) -> Option<(Point, usize)> {
+        self.steps
+            .cells()
+            .filter(|&(point, _)| self.heights[point] == elevation)
+            .filter_map(|(point, &steps)| Some((point, steps?)))
+            .min_by_key(|&(_, steps)| steps)
+    }
+}
 
-                if existing_distance <= new_distance {
-                    continue;
-                }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    points: Vec<Point>,
+}
 
-                distances.insert(neighbour, new_distance);
-                shortest_distance_queue.push(Reverse((new_distance, neighbour)))
-            }
-        }
+impl Route {
+    // Every point visited, from the start to the end.
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
 
-        None
+    pub fn steps(&self) -> usize {
+        self.points.len() - 1
     }
 }
 
@@ -178,7 +229,169 @@ mod tests {
         }
     }
 
+    mod shortest_path {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            rule: ClimbRule,
+            expected: Option<(usize, &'static str)>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                rule: ClimbRule::default(),
+                expected: Some((
+                    31,
+                    ">>vv<<<<
+..vvv<<^
+..vv>E^^
+..v>>>^^
+..>>>>>^",
+                )),
+            })
+        }
+
+        #[test]
+        fn any_ascent() {
+            run(&Case {
+                data: super::example().1,
+                rule: ClimbRule {
+                    max_ascent: u8::MAX,
+                    max_descent: u8::MAX,
+                },
+                expected: Some((
+                    7,
+                    ">>>>>v..
+.....v..
+.....E..
+........
+........",
+                )),
+            })
+        }
+
+        // The example's shortest route never goes down anyway.
+        #[test]
+        fn no_descent() {
+            run(&Case {
+                data: super::example().1,
+                rule: ClimbRule {
+                    max_ascent: 1,
+                    max_descent: 0,
+                },
+                expected: Some((
+                    31,
+                    ">>vv<<<<
+..vvv<<^
+..vv>E^^
+..v>>>^^
+..>>>>>^",
+                )),
+            })
+        }
+
+        #[test]
+        fn no_ascent() {
+            run(&Case {
+                data: super::example().1,
+                rule: ClimbRule {
+                    max_ascent: 0,
+                    max_descent: u8::MAX,
+                },
+                expected: None,
+            })
+        }
+
+        fn run(test: &Case) {
+            let route = test.data.shortest_path(test.data.start(), test.rule);
+            assert_eq!(
+                test.expected
+                    .map(|(steps, render)| (steps, render.to_string())),
+                route
+                    .as_ref()
+                    .map(|route| (route.steps(), test.data.render(route)))
+            );
+
+            if let Some(route) = route {
+                assert_eq!(Some(&test.data.start()), route.points().first());
+                assert_eq!(Some(&test.data.end()), route.points().last());
+                for step in route.points().windows(2) {
+                    let (from, to) = (test.data.heights[step[0]], test.data.heights[step[1]]);
+                    assert!(test.rule.allows(from, to), "{step:?}");
+                }
+            }
+        }
+    }
+
+    mod distances_to_end {
+        use super::*;
+
+        struct Case {
+            elevation: u8,
+            expected: Option<(Point, usize)>,
+        }
+
+        #[test]
+        fn lowest() {
+            run(&Case {
+                elevation: 0,
+                expected: Some(((0, 4), 29)),
+            })
+        }
+
+        #[test]
+        fn highest() {
+            run(&Case {
+                elevation: 25,
+                expected: Some(((5, 2), 0)),
+            })
+        }
+
+        #[test]
+        fn missing() {
+            run(&Case {
+                elevation: 26,
+                expected: None,
+            })
+        }
+
+        fn run(test: &Case) {
+            let (_, input) = super::example();
+            assert_eq!(
+                test.expected,
+                input
+                    .distances_to_end(ClimbRule::default())
+                    .best_start(test.elevation)
+            )
+        }
+
+        #[test]
+        fn matches_shortest_paths() {
+            let (_, input) = example();
+            for rule in [
+                ClimbRule::default(),
+                ClimbRule {
+                    max_ascent: 2,
+                    max_descent: 1,
+                },
+            ] {
+                let distances = input.distances_to_end(rule);
+                for point in input.heights.points() {
+                    assert_eq!(
+                        input.shortest_path(point, rule).map(|route| route.steps()),
+                        distances.steps(point),
+                        "{point:?} {rule:?}"
+                    );
+                }
+            }
+        }
+    }
+
     mod part_1 {
+
         use super::*;
 
         struct Case {
@@ -243,104 +456,18 @@ abcryxxl
 accszExk
 acctuvwj
 abdefghi",
-            #[allow(clippy::eq_op)]
             Input {
                 start: (0, 0),
                 end: (5, 2),
-                grid: vec![
-                    vec![
-                        b'a' - b'a', // S
-                        b'a' - b'a',
-                        b'b' - b'a',
-                        b'q' - b'a',
-                        b'p' - b'a',
-                        b'o' - b'a',
-                        b'n' - b'a',
-                        b'm' - b'a',
-                    ],
-                    vec![
-                        b'a' - b'a',
-                        b'b' - b'a',
-                        b'c' - b'a',
-                        b'r' - b'a',
-                        b'y' - b'a',
-                        b'x' - b'a',
-                        b'x' - b'a',
-                        b'l' - b'a',
-                    ],
-                    vec![
-                        b'a' - b'a',
-                        b'c' - b'a',
-                        b'c' - b'a',
-                        b's' - b'a',
-                        b'z' - b'a',
-                        b'z' - b'a', // E
-                        b'x' - b'a',
-                        b'k' - b'a',
-                    ],
-                    vec![
-                        b'a' - b'a',
-                        b'c' - b'a',
-                        b'c' - b'a',
-                        b't' - b'a',
-                        b'u' - b'a',
-                        b'v' - b'a',
-                        b'w' - b'a',
-                        b'j' - b'a',
-                    ],
-                    vec![
-                        b'a' - b'a',
-                        b'b' - b'a',
-                        b'd' - b'a',
-                        b'e' - b'a',
-                        b'f' - b'a',
-                        b'g' - b'a',
-                        b'h' - b'a',
-                        b'i' - b'a',
-                    ],
-                ],
-                nodes_to_neighbours: HashMap::from([
-                    ((0, 0), vec![(1, 0), (0, 1)]),
-                    ((1, 0), vec![(0, 0), (2, 0), (1, 1)]),
-                    ((2, 0), vec![(1, 0), (2, 1)]),
-                    ((3, 0), vec![(2, 0), (4, 0), (3, 1)]),
-                    ((4, 0), vec![(3, 0), (5, 0)]),
-                    ((5, 0), vec![(4, 0), (6, 0)]),
-                    ((6, 0), vec![(5, 0), (7, 0)]),
-                    ((7, 0), vec![(6, 0), (7, 1)]),
-                    ((0, 1), vec![(0, 0), (1, 1), (0, 2)]),
-                    ((1, 1), vec![(1, 0), (0, 1), (2, 1), (1, 2)]),
-                    ((2, 1), vec![(2, 0), (1, 1), (2, 2)]),
-                    ((3, 1), vec![(3, 0), (2, 1), (3, 2)]),
-                    ((4, 1), vec![(4, 0), (3, 1), (5, 1), (4, 2)]),
-                    ((5, 1), vec![(5, 0), (4, 1), (6, 1)]),
-                    ((6, 1), vec![(6, 0), (5, 1), (7, 1), (6, 2)]),
-                    ((7, 1), vec![(7, 0), (7, 2)]),
-                    ((0, 2), vec![(0, 1), (0, 3)]),
-                    ((1, 2), vec![(1, 1), (0, 2), (2, 2), (1, 3)]),
-                    ((2, 2), vec![(2, 1), (1, 2), (2, 3)]),
-                    ((3, 2), vec![(3, 1), (2, 2), (3, 3)]),
-                    ((4, 2), vec![(4, 1), (3, 2), (5, 2), (4, 3)]),
-                    ((5, 2), vec![(5, 1), (4, 2), (6, 2), (5, 3)]),
-                    ((6, 2), vec![(6, 1), (7, 2), (6, 3)]),
-                    ((7, 2), vec![(7, 1), (7, 3)]),
-                    ((0, 3), vec![(0, 2), (0, 4)]),
-                    ((1, 3), vec![(1, 2), (0, 3), (2, 3), (1, 4)]),
-                    ((2, 3), vec![(2, 2), (1, 3), (2, 4)]),
-                    ((3, 3), vec![(3, 2), (2, 3), (4, 3), (3, 4)]),
-                    ((4, 3), vec![(3, 3), (5, 3), (4, 4)]),
-                    ((5, 3), vec![(4, 3), (6, 3), (5, 4)]),
-                    ((6, 3), vec![(6, 2), (5, 3), (7, 3), (6, 4)]),
-                    ((7, 3), vec![(7, 2), (7, 4)]),
-                    ((0, 4), vec![(0, 3), (1, 4)]),
-                    ((1, 4), vec![(1, 3), (0, 4)]),
-                    ((2, 4), vec![(2, 3), (1, 4), (3, 4)]),
-                    ((3, 4), vec![(2, 4), (4, 4)]),
-                    ((4, 4), vec![(3, 4), (5, 4)]),
-                    ((5, 4), vec![(4, 4), (6, 4)]),
-                    ((6, 4), vec![(5, 4), (7, 4)]),
-                    ((7, 4), vec![(7, 3), (6, 4)]),
-                ]),
+                heights: Grid::parse(
+                    "aabqponm
+abcryxxl
+accszzxk
+acctuvwj
+abdefghi",
+                    |c| Some(c as u8 - b'a'),
+                )
+                .unwrap(),
             },
         )
     }
@@ -1,9 +1,40 @@
-use day_13::{read_data, Input};
+use clap::Parser;
+use day_13::{dividers, read_data, Data, Input};
+
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        long = "divider",
+        value_name = "PACKET",
+        help = "Divider packet to use instead of [[2]] and [[6]] (repeatable)"
+    )]
+    dividers: Vec<Data>,
+
+    #[arg(long, help = "Print every packet and divider in order")]
+    sorted: bool,
+}
 
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
-    let input = Input::from_data(&contents);
+    let input = Input::from_data(&contents).unwrap();
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
+
+    let dividers = if opts.dividers.is_empty() {
+        dividers().to_vec()
+    } else {
+        opts.dividers
+    };
+    if dividers != self::dividers() {
+        println!("Decoder key: {}", input.decoder_key(&dividers));
+    }
+
+    if opts.sorted {
+        for packet in input.sorted_packets(&dividers) {
+            println!("{packet}");
+        }
+    }
 }
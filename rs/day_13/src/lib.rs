@@ -1,4 +1,4 @@
-use std::{fs::read_to_string, path::Path};
+use std::{cmp::Ordering, fmt::Display, fs::read_to_string, path::Path, slice, str::FromStr};
 
 pub const PART_1: usize = 5330;
 pub const PART_2: usize = 27648;
@@ -16,125 +16,366 @@ pub struct Input {
 }
 
 impl Input {
-    pub fn from_data(data: &str) -> Self {
+    pub fn from_data(data: &str) -> Result<Self, Error> {
         let mut pairs = Vec::new();
-        for pair in data.split("\n\n") {
-            let parts = pair.split_once('\n').unwrap();
-            pairs.push([Data::from(parts.0), Data::from(parts.1)]);
+        let mut block = Vec::new();
+        for (line_number, line) in (1..).zip(data.lines().chain([""])) {
+            if !line.trim().is_empty() {
+                let packet = line.parse().map_err(|error| Error::InvalidPacket {
+                    line: line_number,
+                    error,
+                })?;
+                block.push((line_number, packet));
+                continue;
+            }
+
+            match <[_; 2]>::try_from(std::mem::take(&mut block)) {
+                Ok([(_, left), (_, right)]) => pairs.push([left, right]),
+                Err(block) if block.is_empty() => {}
+                Err(block) => {
+                    return Err(Error::InvalidPair {
+                        line: block[0].0,
+                        packets: block.len(),
+                    })
+                }
+            }
         }
-        Self { pairs }
+        Ok(Self { pairs })
+    }
+
+    pub fn pairs(&self) -> &[[Data; 2]] {
+        &self.pairs
     }
 
     pub fn part_1(&self) -> usize {
-        self.pairs
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, pair)| {
-                if matches!(
-                    pair[0].partial_cmp(&pair[1]),
-                    Some(std::cmp::Ordering::Less)
-                ) {
-                    Some(idx + 1)
-                } else {
-                    None
-                }
-            })
+        (1..)
+            .zip(&self.pairs)
+            .filter(|(_, [left, right])| left.packet_cmp(right).is_lt())
+            .map(|(idx, _)| idx)
             .sum()
     }
 
     pub fn part_2(&self) -> usize {
-        let divider = [
-            Data::List(vec![Data::List(vec![Data::Integer(2)])]),
-            Data::List(vec![Data::List(vec![Data::Integer(6)])]),
-        ];
+        self.decoder_key(&dividers())
+    }
 
-        let mut packets = self
-            .pairs
-            .iter()
-            .flatten()
-            .chain(&divider)
-            .collect::<Vec<&Data>>();
+    pub fn packets(&self) -> impl Iterator<Item = &Data> {
+        self.pairs.iter().flatten()
+    }
 
-        packets.sort();
+    // Every packet along with the dividers, in order.
+    pub fn sorted_packets<'d>(&'d self, dividers: &'d [Data]) -> Vec<&'d Data> {
+        let mut packets = self.packets().chain(dividers).collect::<Vec<_>>();
+        packets.sort_unstable();
+        packets
+    }
 
-        divider
+    // Returns where each divider ends up, counting from 1, once the dividers
+    // are sorted in with the packets.
+    pub fn divider_indices(&self, dividers: &[Data]) -> Vec<usize> {
+        dividers
             .iter()
-            .map(|packet| packets.iter().position(|&p| p.eq(packet)).unwrap() + 1)
-            .product()
+            .map(|divider| {
+                1 + self
+                    .packets()
+                    .chain(dividers)
+                    .filter(|&packet| packet < divider)
+                    .count()
+            })
+            .collect()
+    }
+
+    pub fn decoder_key(&self, dividers: &[Data]) -> usize {
+        self.divider_indices(dividers).iter().product()
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum Data {
-    Integer(u8),
+// The divider packets `[[2]]` and `[[6]]`.
+pub fn dividers() -> [Data; 2] {
+    [2, 6].map(|value| Data::List(vec![Data::List(vec![Data::Integer(value.into())])]))
+}
+
+pub fn sort_packets(packets: &mut [Data]) {
+    packets.sort_unstable();
+}
+
+// A packet, written as a JSON array of non-negative integers and arrays.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Data {
+    Integer(Integer),
     List(Vec<Data>),
 }
 
-impl From<&str> for Data {
-    fn from(input: &str) -> Self {
-        fn parse_part(input: &str) -> (Data, &str) {
-            let mut inp = input.strip_prefix('[').unwrap_or(input);
-            let mut output = Vec::new();
-            while !inp.is_empty() {
-                if let Some(remainder) = inp.strip_prefix(']').map(|p| p.trim_start_matches(',')) {
-                    return (Data::List(output), remainder);
+impl Data {
+    fn parse(tokens: &mut Tokens) -> Result<Self, PacketError> {
+        match tokens.next().transpose()? {
+            Some((_, Token::Integer(digits))) => Ok(Data::Integer(Integer::from_digits(digits))),
+            Some((_, Token::Open)) => {
+                let mut list = Vec::new();
+                if tokens
+                    .next_if(|token| matches!(token, Ok((_, Token::Close))))
+                    .is_some()
+                {
+                    return Ok(Data::List(list));
                 }
-                if inp.starts_with('[') {
-                    let res = parse_part(inp);
-                    output.push(res.0);
-                    inp = res.1;
-                    continue;
+                loop {
+                    list.push(Data::parse(tokens)?);
+                    match tokens.next().transpose()? {
+                        Some((_, Token::Comma)) => continue,
+                        Some((_, Token::Close)) => return Ok(Data::List(list)),
+                        Some((column, token)) => {
+                            return Err(PacketError::UnexpectedToken {
+                                column,
+                                token: token.to_string(),
+                            })
+                        }
+                        None => return Err(PacketError::UnexpectedEnd),
+                    }
                 }
+            }
+            Some((column, token)) => Err(PacketError::UnexpectedToken {
+                column,
+                token: token.to_string(),
+            }),
+            None => Err(PacketError::UnexpectedEnd),
+        }
+    }
+}
 
-                match inp.find(&[',', ']'][..]) {
-                    Some(delim_idx) => {
-                        output.push(Data::Integer(inp[..delim_idx].parse().unwrap()));
-                        inp = &inp[delim_idx..];
-                    }
-                    None => {
-                        output.push(Data::Integer(inp.parse().unwrap()));
-                        inp = "";
+impl Data {
+    // Compares integers by value and lists item by item and then by length,
+    // treating an integer compared with a list as a list holding just it.
+    pub fn packet_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Data::Integer(left), Data::Integer(right)) => left.cmp(right),
+            (Data::Integer(_), Data::List(right)) => Self::list_cmp(slice::from_ref(self), right),
+            (Data::List(left), Data::Integer(_)) => Self::list_cmp(left, slice::from_ref(other)),
+            (Data::List(left), Data::List(right)) => Self::list_cmp(left, right),
+        }
+    }
+
+    fn list_cmp(left: &[Data], right: &[Data]) -> Ordering {
+        left.iter()
+            .zip(right)
+            .map(|(lhs, rhs)| lhs.packet_cmp(rhs))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| left.len().cmp(&right.len()))
+    }
+
+    fn structural_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Data::Integer(left), Data::Integer(right)) => left.cmp(right),
+            (Data::Integer(_), Data::List(_)) => Ordering::Less,
+            (Data::List(_), Data::Integer(_)) => Ordering::Greater,
+            (Data::List(left), Data::List(right)) => left
+                .iter()
+                .zip(right)
+                .map(|(lhs, rhs)| lhs.structural_cmp(rhs))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| left.len().cmp(&right.len())),
+        }
+    }
+}
+
+impl FromStr for Data {
+    type Err = PacketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Token::tokenize(s).peekable();
+        let data = Data::parse(&mut tokens)?;
+        match tokens.next().transpose()? {
+            Some((column, token)) => Err(PacketError::UnexpectedToken {
+                column,
+                token: token.to_string(),
+            }),
+            None => Ok(data),
+        }
+    }
+}
+
+impl Display for Data {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Data::Integer(integer) => write!(f, "{integer}"),
+            Data::List(list) => {
+                f.write_str("[")?;
+                for (idx, item) in list.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
                     }
+                    write!(f, "{item}")?;
                 }
-                inp = inp.trim_start_matches(',');
+                f.write_str("]")
             }
+        }
+    }
+}
 
-            (Data::List(output), "")
+// A non-negative integer of any size, kept as its decimal digits without
+// leading zeros so that longer means larger.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Integer(String);
+
+impl Integer {
+    fn from_digits(digits: &str) -> Self {
+        match digits.trim_start_matches('0') {
+            "" => Self("0".to_string()),
+            digits => Self(digits.to_string()),
         }
+    }
+}
 
-        parse_part(input).0
+impl From<u64> for Integer {
+    fn from(value: u64) -> Self {
+        Self(value.to_string())
     }
 }
 
-impl PartialOrd for Data {
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.0.len(), &self.0).cmp(&(other.0.len(), &other.0))
+    }
+}
+
+impl PartialOrd for Integer {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match (self, other) {
-            (Data::Integer(left), Data::Integer(right)) => match left.cmp(right) {
-                std::cmp::Ordering::Less => Some(std::cmp::Ordering::Less),
-                std::cmp::Ordering::Equal => None,
-                std::cmp::Ordering::Greater => Some(std::cmp::Ordering::Greater),
-            },
-            (&Data::Integer(left), Data::List(_)) => {
-                Data::List(vec![Data::Integer(left)]).partial_cmp(other)
-            }
-            (Data::List(_), &Data::Integer(right)) => {
-                self.partial_cmp(&Data::List(vec![Data::Integer(right)]))
-            }
-            (Data::List(left), Data::List(right)) => {
-                for (lhs, rhs) in left.iter().zip(right) {
-                    if let Some(comparison) = lhs.partial_cmp(rhs) {
-                        return Some(comparison);
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+type Tokens<'s> = std::iter::Peekable<Tokenizer<'s>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'s> {
+    Open,
+    Close,
+    Comma,
+    Integer(&'s str),
+}
+
+impl<'s> Token<'s> {
+    fn tokenize(text: &'s str) -> Tokenizer<'s> {
+        Tokenizer {
+            text,
+            chars: text.char_indices().peekable(),
+            column: 0,
+        }
+    }
+}
+
+// Splits a packet into tokens paired with their (1-based) column, skipping
+// whitespace.
+struct Tokenizer<'s> {
+    text: &'s str,
+    chars: std::iter::Peekable<std::str::CharIndices<'s>>,
+    column: usize,
+}
+
+impl<'s> Iterator for Tokenizer<'s> {
+    type Item = Result<(usize, Token<'s>), PacketError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, character) = self.chars.next()?;
+            self.column += 1;
+            let column = self.column;
+            let token = match character {
+                '[' => Token::Open,
+                ']' => Token::Close,
+                ',' => Token::Comma,
+                character if character.is_whitespace() => continue,
+                character if character.is_ascii_digit() => {
+                    let mut end = start + 1;
+                    while let Some((idx, _)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                        self.column += 1;
+                        end = idx + 1;
                     }
+                    Token::Integer(&self.text[start..end])
                 }
-                Data::Integer(left.len() as u8).partial_cmp(&Data::Integer(right.len() as u8))
+                character => {
+                    return Some(Err(PacketError::UnexpectedCharacter { column, character }))
+                }
+            };
+            return Some(Ok((column, token)));
+        }
+    }
+}
+
+impl Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Open => f.write_str("["),
+            Token::Close => f.write_str("]"),
+            Token::Comma => f.write_str(","),
+            Token::Integer(digits) => f.write_str(digits),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketError {
+    UnexpectedCharacter { column: usize, character: char },
+    UnexpectedToken { column: usize, token: String },
+    UnexpectedEnd,
+}
+
+impl Display for PacketError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PacketError::UnexpectedCharacter { column, character } => {
+                write!(f, "column {column}: unexpected character {character:?}")
+            }
+            PacketError::UnexpectedToken { column, token } => {
+                write!(f, "column {column}: unexpected {token:?}")
+            }
+            PacketError::UnexpectedEnd => f.write_str("unexpected end of packet"),
+        }
+    }
+}
+
+impl std::error::Error for PacketError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    InvalidPacket { line: usize, error: PacketError },
+    InvalidPair { line: usize, packets: usize },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidPacket { line, error } => write!(f, "line {line}: {error}"),
+            Error::InvalidPair { line, packets } => {
+                write!(
+                    f,
+                    "line {line}: expected a pair of packets, found {packets}"
+                )
             }
         }
     }
 }
 
+impl std::error::Error for Error {}
+
+// Packets are ordered by how the distress signal compares them, with ties
+// between packets like `1` and `[[1]]`, which the signal treats as equal,
+// broken by putting integers before lists.
 impl Ord for Data {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Equal)
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.packet_cmp(other)
+            .then_with(|| self.structural_cmp(other))
+    }
+}
+
+impl PartialOrd for Data {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -149,14 +390,71 @@ mod tests {
 
         struct Case<'c> {
             input: &'c str,
-            expected: Input,
+            expected: Result<Input, Error>,
         }
 
         #[test]
         fn example() {
             run(&Case {
                 input: super::example().0,
-                expected: super::example().1,
+                expected: Ok(super::example().1),
+            })
+        }
+
+        #[test]
+        fn whitespace_and_large_integers() {
+            run(&Case {
+                input: " [ 1 , [ ] ]\n[00340282366920938463463374607431768211456]\n\n\n[0]\n[[]]\n",
+                expected: Ok(Input {
+                    pairs: vec![
+                        [
+                            Data::List(vec![Data::Integer(1.into()), Data::List(vec![])]),
+                            Data::List(vec![Data::Integer(Integer(
+                                "340282366920938463463374607431768211456".to_string(),
+                            ))]),
+                        ],
+                        [
+                            Data::List(vec![Data::Integer(0.into())]),
+                            Data::List(vec![Data::List(vec![])]),
+                        ],
+                    ],
+                }),
+            })
+        }
+
+        #[test]
+        fn invalid_packet() {
+            run(&Case {
+                input: "[1,2]\n[1,,2]",
+                expected: Err(Error::InvalidPacket {
+                    line: 2,
+                    error: PacketError::UnexpectedToken {
+                        column: 4,
+                        token: ",".to_string(),
+                    },
+                }),
+            })
+        }
+
+        #[test]
+        fn unpaired_packet() {
+            run(&Case {
+                input: "[1]\n[2]\n\n[3]",
+                expected: Err(Error::InvalidPair {
+                    line: 4,
+                    packets: 1,
+                }),
+            })
+        }
+
+        #[test]
+        fn too_many_packets() {
+            run(&Case {
+                input: "[1]\n[2]\n[3]",
+                expected: Err(Error::InvalidPair {
+                    line: 1,
+                    packets: 3,
+                }),
             })
         }
 
@@ -165,7 +463,265 @@ mod tests {
         }
     }
 
+    mod from_str {
+        use super::*;
+
+        struct Case<'c> {
+            input: &'c str,
+            expected: Result<Data, PacketError>,
+        }
+
+        #[test]
+        fn integer() {
+            run(&Case {
+                input: "42",
+                expected: Ok(Data::Integer(42.into())),
+            })
+        }
+
+        #[test]
+        fn unexpected_character() {
+            run(&Case {
+                input: "[1, -2]",
+                expected: Err(PacketError::UnexpectedCharacter {
+                    column: 5,
+                    character: '-',
+                }),
+            })
+        }
+
+        #[test]
+        fn unclosed() {
+            run(&Case {
+                input: "[[1],[2]",
+                expected: Err(PacketError::UnexpectedEnd),
+            })
+        }
+
+        #[test]
+        fn trailing_comma() {
+            run(&Case {
+                input: "[1,]",
+                expected: Err(PacketError::UnexpectedToken {
+                    column: 4,
+                    token: "]".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn trailing_tokens() {
+            run(&Case {
+                input: "[1] [2]",
+                expected: Err(PacketError::UnexpectedToken {
+                    column: 5,
+                    token: "[".to_string(),
+                }),
+            })
+        }
+
+        #[test]
+        fn empty() {
+            run(&Case {
+                input: "  ",
+                expected: Err(PacketError::UnexpectedEnd),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.expected, test.input.parse())
+        }
+    }
+
+    mod display {
+        #[test]
+        fn round_trip() {
+            let (input, data) = super::example();
+            let printed = data
+                .pairs()
+                .iter()
+                .map(|[left, right]| format!("{left}\n{right}"))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            assert_eq!(input, printed);
+        }
+    }
+
+    mod packet_cmp {
+        use super::*;
+
+        struct Case {
+            left: &'static str,
+            right: &'static str,
+            expected: Ordering,
+        }
+
+        #[test]
+        fn equal_integers_continue() {
+            run(&Case {
+                left: "[1,1,3]",
+                right: "[1,1,5]",
+                expected: Ordering::Less,
+            })
+        }
+
+        #[test]
+        fn integer_against_list() {
+            run(&Case {
+                left: "[[1],[2,3,4]]",
+                right: "[[1],4]",
+                expected: Ordering::Less,
+            })
+        }
+
+        #[test]
+        fn wrapped_integers_tie() {
+            run(&Case {
+                left: "[1]",
+                right: "[[[1]]]",
+                expected: Ordering::Equal,
+            })
+        }
+
+        fn run(test: &Case) {
+            let (left, right) = (
+                test.left.parse::<Data>().unwrap(),
+                test.right.parse().unwrap(),
+            );
+            assert_eq!(test.expected, left.packet_cmp(&right));
+            assert_eq!(test.expected.reverse(), right.packet_cmp(&left));
+        }
+
+        #[test]
+        fn long_lists() {
+            let long = Data::List(vec![Data::Integer(0.into()); 256]);
+            assert_eq!(Ordering::Less, Data::List(vec![]).packet_cmp(&long));
+            assert_eq!(Ordering::Greater, long.packet_cmp(&Data::List(vec![])));
+        }
+
+        #[test]
+        fn ties_are_broken() {
+            let (left, right) = ("[1]".parse::<Data>().unwrap(), "[[1]]".parse().unwrap());
+            assert_eq!(Ordering::Less, left.cmp(&right));
+            assert_eq!(Ordering::Greater, right.cmp(&left));
+        }
+    }
+
+    mod order_laws {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn data() -> impl Strategy<Value = Data> {
+            let integer = (0u64..8).prop_map(|value| Data::Integer(value.into()));
+            integer.prop_recursive(4, 32, 4, |item| {
+                prop::collection::vec(item, 0..4).prop_map(Data::List)
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn consistent_with_eq(left in data(), right in data()) {
+                prop_assert_eq!(left == right, left.cmp(&right).is_eq());
+                prop_assert_eq!(left.cmp(&right), right.cmp(&left).reverse());
+                prop_assert_eq!(left.packet_cmp(&right), right.packet_cmp(&left).reverse());
+            }
+
+            #[test]
+            fn transitive(packets in [data(), data(), data()]) {
+                let mut sorted = packets.clone();
+                sort_packets(&mut sorted);
+                prop_assert!(sorted[0] <= sorted[1] && sorted[1] <= sorted[2]);
+                prop_assert!(sorted[0] <= sorted[2]);
+                prop_assert!(sorted[0].packet_cmp(&sorted[2]).is_le());
+            }
+
+            #[test]
+            fn refines_packet_order(left in data(), right in data()) {
+                let packet_order = left.packet_cmp(&right);
+                prop_assert!(packet_order.is_eq() || packet_order == left.cmp(&right));
+            }
+
+            #[test]
+            fn round_trips(packet in data()) {
+                prop_assert_eq!(Ok(packet.clone()), packet.to_string().parse());
+            }
+        }
+    }
+
+    mod sorted_packets {
+        #[test]
+        fn example() {
+            let (_, input) = super::example();
+            let dividers = super::dividers();
+            assert_eq!(
+                "[]
+[[]]
+[[[]]]
+[1,1,3,1,1]
+[1,1,5,1,1]
+[[1],[2,3,4]]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[[1],4]
+[[2]]
+[3]
+[[4,4],4,4]
+[[4,4],4,4,4]
+[[6]]
+[7,7,7]
+[7,7,7,7]
+[[8,7,6]]
+[9]",
+                input
+                    .sorted_packets(&dividers)
+                    .iter()
+                    .map(|packet| packet.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+    }
+
+    mod divider_indices {
+        use super::*;
+
+        struct Case {
+            dividers: Vec<Data>,
+            expected: Vec<usize>,
+        }
+
+        #[test]
+        fn default_dividers() {
+            run(&Case {
+                dividers: dividers().to_vec(),
+                expected: vec![10, 14],
+            })
+        }
+
+        #[test]
+        fn custom_dividers() {
+            run(&Case {
+                dividers: vec!["[5]".parse().unwrap(), "[[8]]".parse().unwrap()],
+                expected: vec![13, 16],
+            })
+        }
+
+        #[test]
+        fn no_dividers() {
+            run(&Case {
+                dividers: vec![],
+                expected: vec![],
+            })
+        }
+
+        fn run(test: &Case) {
+            let (_, input) = super::example();
+            assert_eq!(test.expected, input.divider_indices(&test.dividers));
+        }
+    }
+
     mod part_1 {
+
         use super::*;
 
         struct Case {
@@ -184,7 +740,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_1,
             })
         }
@@ -213,7 +769,7 @@ mod tests {
         #[test]
         fn actual() {
             run(&Case {
-                data: Input::from_data(&read_data(DATA_DIR)),
+                data: Input::from_data(&read_data(DATA_DIR)).unwrap(),
                 expected: PART_2,
             })
         }
@@ -252,100 +808,114 @@ mod tests {
                 pairs: vec![
                     [
                         Data::List(vec![
-                            Data::Integer(1),
-                            Data::Integer(1),
-                            Data::Integer(3),
-                            Data::Integer(1),
-                            Data::Integer(1),
+                            Data::Integer(1.into()),
+                            Data::Integer(1.into()),
+                            Data::Integer(3.into()),
+                            Data::Integer(1.into()),
+                            Data::Integer(1.into()),
                         ]),
                         Data::List(vec![
-                            Data::Integer(1),
-                            Data::Integer(1),
-                            Data::Integer(5),
-                            Data::Integer(1),
-                            Data::Integer(1),
+                            Data::Integer(1.into()),
+                            Data::Integer(1.into()),
+                            Data::Integer(5.into()),
+                            Data::Integer(1.into()),
+                            Data::Integer(1.into()),
                         ]),
                     ],
                     [
                         Data::List(vec![
-                            Data::List(vec![Data::Integer(1)]),
-                            Data::List(vec![Data::Integer(2), Data::Integer(3), Data::Integer(4)]),
+                            Data::List(vec![Data::Integer(1.into())]),
+                            Data::List(vec![
+                                Data::Integer(2.into()),
+                                Data::Integer(3.into()),
+                                Data::Integer(4.into()),
+                            ]),
+                        ]),
+                        Data::List(vec![
+                            Data::List(vec![Data::Integer(1.into())]),
+                            Data::Integer(4.into()),
                         ]),
-                        Data::List(vec![Data::List(vec![Data::Integer(1)]), Data::Integer(4)]),
                     ],
                     [
-                        Data::List(vec![Data::Integer(9)]),
+                        Data::List(vec![Data::Integer(9.into())]),
                         Data::List(vec![Data::List(vec![
-                            Data::Integer(8),
-                            Data::Integer(7),
-                            Data::Integer(6),
+                            Data::Integer(8.into()),
+                            Data::Integer(7.into()),
+                            Data::Integer(6.into()),
                         ])]),
                     ],
                     [
                         Data::List(vec![
-                            Data::List(vec![Data::Integer(4), Data::Integer(4)]),
-                            Data::Integer(4),
-                            Data::Integer(4),
+                            Data::List(vec![Data::Integer(4.into()), Data::Integer(4.into())]),
+                            Data::Integer(4.into()),
+                            Data::Integer(4.into()),
                         ]),
                         Data::List(vec![
-                            Data::List(vec![Data::Integer(4), Data::Integer(4)]),
-                            Data::Integer(4),
-                            Data::Integer(4),
-                            Data::Integer(4),
+                            Data::List(vec![Data::Integer(4.into()), Data::Integer(4.into())]),
+                            Data::Integer(4.into()),
+                            Data::Integer(4.into()),
+                            Data::Integer(4.into()),
                         ]),
                     ],
                     [
                         Data::List(vec![
-                            Data::Integer(7),
-                            Data::Integer(7),
-                            Data::Integer(7),
-                            Data::Integer(7),
+                            Data::Integer(7.into()),
+                            Data::Integer(7.into()),
+                            Data::Integer(7.into()),
+                            Data::Integer(7.into()),
                         ]),
-                        Data::List(vec![Data::Integer(7), Data::Integer(7), Data::Integer(7)]),
+                        Data::List(vec![
+                            Data::Integer(7.into()),
+                            Data::Integer(7.into()),
+                            Data::Integer(7.into()),
+                        ]),
+                    ],
+                    [
+                        Data::List(vec![]),
+                        Data::List(vec![Data::Integer(3.into())]),
                     ],
-                    [Data::List(vec![]), Data::List(vec![Data::Integer(3)])],
                     [
                         Data::List(vec![Data::List(vec![Data::List(vec![])])]),
                         Data::List(vec![Data::List(vec![])]),
                     ],
                     [
                         Data::List(vec![
-                            Data::Integer(1),
+                            Data::Integer(1.into()),
                             Data::List(vec![
-                                Data::Integer(2),
+                                Data::Integer(2.into()),
                                 Data::List(vec![
-                                    Data::Integer(3),
+                                    Data::Integer(3.into()),
                                     Data::List(vec![
-                                        Data::Integer(4),
+                                        Data::Integer(4.into()),
                                         Data::List(vec![
-                                            Data::Integer(5),
-                                            Data::Integer(6),
-                                            Data::Integer(7),
+                                            Data::Integer(5.into()),
+                                            Data::Integer(6.into()),
+                                            Data::Integer(7.into()),
                                         ]),
                                     ]),
                                 ]),
                             ]),
-                            Data::Integer(8),
-                            Data::Integer(9),
+                            Data::Integer(8.into()),
+                            Data::Integer(9.into()),
                         ]),
                         Data::List(vec![
-                            Data::Integer(1),
+                            Data::Integer(1.into()),
                             Data::List(vec![
-                                Data::Integer(2),
+                                Data::Integer(2.into()),
                                 Data::List(vec![
-                                    Data::Integer(3),
+                                    Data::Integer(3.into()),
                                     Data::List(vec![
-                                        Data::Integer(4),
+                                        Data::Integer(4.into()),
                                         Data::List(vec![
-                                            Data::Integer(5),
-                                            Data::Integer(6),
-                                            Data::Integer(0),
+                                            Data::Integer(5.into()),
+                                            Data::Integer(6.into()),
+                                            Data::Integer(0.into()),
                                         ]),
                                     ]),
                                 ]),
                             ]),
-                            Data::Integer(8),
-                            Data::Integer(9),
+                            Data::Integer(8.into()),
+                            Data::Integer(9.into()),
                         ]),
                     ],
                 ],
@@ -1,9 +1,113 @@
-use day_14::{read_data, Input};
+use std::{fs, num::NonZeroUsize, path::PathBuf, thread, time::Duration};
+
+use clap::Parser;
+use day_14::{read_data, Config, Input};
+
+// The shared runner only reports answers, so drawing the cave as it fills is
+// done from here.
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        long,
+        value_name = "X,Y",
+        value_parser = parse_point,
+        help = "Pour sand from X,Y instead of 500,0"
+    )]
+    source: Option<(usize, usize)>,
+
+    #[arg(
+        long,
+        value_name = "ROWS",
+        help = "Count sand with a floor ROWS below the lowest rock, instead of an abyss"
+    )]
+    floor_offset: Option<usize>,
+
+    #[arg(long, help = "Draw the cave once no more sand will settle")]
+    render: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Write a drawing of the cave to DIR as it fills with sand"
+    )]
+    frames_dir: Option<PathBuf>,
+
+    #[arg(long, help = "Draw the cave in the terminal as it fills with sand")]
+    play: bool,
+
+    #[arg(
+        long,
+        value_name = "GRAINS",
+        default_value = "1",
+        help = "Grains of sand to settle between frames"
+    )]
+    every: NonZeroUsize,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = 50,
+        help = "Pause between frames when playing"
+    )]
+    delay_ms: u64,
+}
+
+fn parse_point(text: &str) -> Result<(usize, usize), String> {
+    let (x, y) = text
+        .split_once(',')
+        .ok_or_else(|| format!("expected X,Y, got {text:?}"))?;
+    let parse = |n: &str| n.trim().parse::<usize>().map_err(|e| e.to_string());
+    Ok((parse(x)?, parse(y)?))
+}
 
 fn main() {
+    let opts = Opts::parse();
+
     let contents = read_data("./data");
     let input = Input::from_data(&contents);
 
     println!("Part 1: {}", input.part_1());
     println!("Part 2: {}", input.part_2());
+
+    let config = Config {
+        source: opts.source.unwrap_or(Config::default().source),
+        floor: opts.floor_offset,
+    };
+    let cave = match input.cave(config) {
+        Ok(cave) => cave,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+
+    if opts.source.is_some() || opts.floor_offset.is_some() || opts.render {
+        let mut cave = cave.clone();
+        let settled = match cave.flood_fill() {
+            Some(settled) => settled,
+            None => cave.fill(),
+        };
+        println!("Settled from {:?}: {settled}", config.source);
+        if opts.render {
+            println!("{}", cave.render());
+        }
+    }
+
+    if let Some(dir) = &opts.frames_dir {
+        fs::create_dir_all(dir).unwrap();
+        let mut count = 0;
+        for (idx, frame) in cave.clone().frames(opts.every).enumerate() {
+            fs::write(dir.join(format!("frame_{idx:05}.txt")), frame + "\n").unwrap();
+            count += 1;
+        }
+        println!("Wrote {count} frame(s) to {}", dir.display());
+    }
+
+    if opts.play {
+        for frame in cave.frames(opts.every) {
+            // Clear the screen and move the cursor home before each frame.
+            println!("\x1b[2J\x1b[H{frame}");
+            thread::sleep(Duration::from_millis(opts.delay_ms));
+        }
+    }
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashSet, fs::read_to_string, path::Path};
+use std::{fmt::Display, fs::read_to_string, num::NonZeroUsize, path::Path};
+
+use grid::{Grid, SparseGrid};
 
 pub const PART_1: usize = 592;
 pub const PART_2: usize = 30367;
@@ -12,13 +14,13 @@ pub fn read_data(data_dir: &str) -> String {
 
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct Input {
-    max_y: usize,
-    rocks: HashSet<(usize, usize)>,
+    max_y: i64,
+    cave: SparseGrid<Tile>,
 }
 
 impl Input {
     pub fn from_data(data: &str) -> Self {
-        let mut rocks = HashSet::new();
+        let mut cave = SparseGrid::new();
         let mut max_y = 0;
         for line in data.lines() {
             let points = line
@@ -27,96 +29,338 @@ impl Input {
                     let (lhs, rhs) = point.split_once(',').unwrap();
                     (lhs.parse().unwrap(), rhs.parse().unwrap())
                 })
-                .collect::<Vec<(usize, usize)>>();
+                .collect::<Vec<(i64, i64)>>();
             for window in points.windows(2) {
                 let (from, to) = (window[0], window[1]);
                 for x in from.0.min(to.0)..=to.0.max(from.0) {
                     for y in from.1.min(to.1)..=to.1.max(from.1) {
                         max_y = max_y.max(y);
-                        rocks.insert((x, y));
+                        cave.insert((x, y), Tile::Rock);
                     }
                 }
             }
         }
-        Self { max_y, rocks }
+        Self { max_y, cave }
     }
 
     pub fn part_1(&self) -> usize {
-        let sand_start = (500, 0);
+        self.cave(Config::default()).unwrap().fill()
+    }
+
+    pub fn part_2(&self) -> usize {
+        let config = Config {
+            floor: Some(2),
+            ..Config::default()
+        };
+        self.cave(config).unwrap().flood_fill().unwrap()
+    }
+
+    pub fn cave(&self, config: Config) -> Result<Cave, Error> {
+        Cave::new(&self.cave, self.max_y as usize, config)
+    }
+}
 
-        let mut filled = self.rocks.clone();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub source: (usize, usize),
+    // How far below the lowest rock the floor is, if there is one.
+    pub floor: Option<usize>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            source: (500, 0),
+            floor: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tile {
+    #[default]
+    Air,
+    Rock,
+    Sand,
+}
 
-        let mut sand = sand_start;
-        let mut count = 0;
-        loop {
-            if sand.1 > self.max_y {
-                return count;
+// What happened to a grain of sand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grain {
+    Settled((usize, usize)),
+    Abyss,
+    Blocked,
+}
+
+// A dense slice of the cave, wide enough that sand never leaves it without
+// falling into the abyss. Points are in the puzzle's coordinates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cave {
+    tiles: Grid<Tile>,
+    left: usize,
+    source: (usize, usize),
+    lowest_rock: usize,
+    floor: Option<usize>,
+    // Where the last grain fell through. The next one takes the same route
+    // until just above where the last one settled, so it resumes from there.
+    path: Vec<(usize, usize)>,
+    settled: usize,
+}
+
+impl Cave {
+    fn new(rocks: &SparseGrid<Tile>, lowest_rock: usize, config: Config) -> Result<Self, Error> {
+        let (source_x, source_y) = config.source;
+        let floor = config.floor.map(|offset| lowest_rock + offset);
+        if let Some(floor) = floor {
+            // On a floor, sand piles up to one column further out on each side
+            // for every row it falls, and there's nowhere to put it left of 0.
+            if source_y > floor {
+                return Err(Error::SourceBelowFloor {
+                    source: config.source,
+                    floor,
+                });
             }
-            let straight_down = (sand.0, sand.1 + 1);
-            if !filled.contains(&straight_down) {
-                sand = straight_down;
-                continue;
+            if source_x + 1 < floor - source_y {
+                return Err(Error::SpreadsPastEdge {
+                    source: config.source,
+                });
             }
-            let left_down = (straight_down.0 - 1, straight_down.1);
-            if !filled.contains(&left_down) {
-                sand = left_down;
-                continue;
+        }
+        let bottom = floor.unwrap_or(lowest_rock + 1);
+
+        // Sand spreads at most one column sideways for each row it falls.
+        let spread = bottom.saturating_sub(source_y) + 1;
+        let ((min_x, _), (max_x, _)) = rocks.bounds().unwrap_or_default();
+        let left = (min_x as usize).min(source_x.saturating_sub(spread));
+        let right = (max_x as usize).max(source_x + spread);
+
+        let mut tiles = Grid::new(right - left + 1, bottom + 1, Tile::Air);
+        for ((x, y), &tile) in rocks.iter() {
+            tiles[(x as usize - left, y as usize)] = tile;
+        }
+        if let Some(floor) = floor {
+            for x in 0..tiles.width() {
+                tiles[(x, floor)] = Tile::Rock;
             }
-            let right_down = (straight_down.0 + 1, straight_down.1);
-            if !filled.contains(&right_down) {
-                sand = right_down;
-                continue;
+        }
+
+        Ok(Self {
+            tiles,
+            left,
+            source: config.source,
+            lowest_rock,
+            floor,
+            path: Vec::new(),
+            settled: 0,
+        })
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<Tile> {
+        self.tiles.get((x.checked_sub(self.left)?, y)).copied()
+    }
+
+    pub fn source(&self) -> (usize, usize) {
+        self.source
+    }
+
+    pub fn settled(&self) -> usize {
+        self.settled
+    }
+
+    // Draws the cave as the puzzle does, cropped to the source, the rocks
+    // and the sand. A floor is drawn along the bottom, as wide as the rest.
+    pub fn render(&self) -> String {
+        let (source_x, source_y) = (self.source.0 - self.left, self.source.1);
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (source_x, source_x, source_y, source_y);
+        for ((x, y), &tile) in self.tiles.cells() {
+            if tile != Tile::Air && Some(y) != self.floor {
+                (min_x, max_x) = (min_x.min(x), max_x.max(x));
+                (min_y, max_y) = (min_y.min(y), max_y.max(y));
             }
+        }
+        if let Some(floor) = self.floor {
+            max_y = floor;
+        }
 
-            filled.insert(sand);
-            sand = sand_start;
-            count += 1;
+        let mut output = String::new();
+        for y in min_y..=max_y {
+            if y > min_y {
+                output.push('\n');
+            }
+            for x in min_x..=max_x {
+                output.push(match self.tiles[(x, y)] {
+                    Tile::Rock => '#',
+                    Tile::Sand => 'o',
+                    Tile::Air if (x, y) == (source_x, source_y) => '+',
+                    Tile::Air => '.',
+                });
+            }
         }
+        output
     }
 
-    pub fn part_2(&self) -> usize {
-        let floor = self.max_y + 2;
-        let sand_start = (500, 0);
+    // Renders the cave as it fills with sand: first as it is, then after
+    // every `every` grains settle, then once more when no more will.
+    pub fn frames(self, every: NonZeroUsize) -> Frames {
+        Frames {
+            cave: self,
+            every: every.get(),
+            started: false,
+            finished: false,
+        }
+    }
 
-        let mut filled = self.rocks.clone();
+    pub fn drop_grain(&mut self) -> Grain {
+        if self.path.is_empty() {
+            match self.get(self.source) {
+                Some(Tile::Air) => self.path.push(self.source),
+                Some(_) => return Grain::Blocked,
+                // Only a bottomless cave lets the source lie beyond the grid.
+                None => return Grain::Abyss,
+            }
+        }
 
-        let mut sand = sand_start;
-        let mut count = 0;
-        loop {
-            if sand.1 + 1 == floor {
-                filled.insert(sand);
-                sand = sand_start;
-                count += 1;
-                continue;
+        while let Some(&(x, y)) = self.path.last() {
+            if self.get((x, y)).is_none() || (self.floor.is_none() && y > self.lowest_rock) {
+                return Grain::Abyss;
             }
 
-            let straight_down = (sand.0, sand.1 + 1);
-            if !filled.contains(&straight_down) {
-                sand = straight_down;
-                continue;
+            let mut below = None;
+            for x in [Some(x), x.checked_sub(1), Some(x + 1)] {
+                // Left of 0 there's only the floor to stop sand, and a cave
+                // with a floor never lets sand get any higher there.
+                let Some(x) = x else {
+                    if self.floor == Some(y + 1) {
+                        continue;
+                    }
+                    return Grain::Abyss;
+                };
+                if matches!(self.get((x, y + 1)), Some(Tile::Air) | None) {
+                    below = Some((x, y + 1));
+                    break;
+                }
             }
-            let left_down = (straight_down.0 - 1, straight_down.1);
-            if !filled.contains(&left_down) {
-                sand = left_down;
-                continue;
+            match below {
+                Some(point) => self.path.push(point),
+                None => {
+                    self.tiles[(x - self.left, y)] = Tile::Sand;
+                    self.path.pop();
+                    self.settled += 1;
+                    return Grain::Settled((x, y));
+                }
             }
-            let right_down = (straight_down.0 + 1, straight_down.1);
-            if !filled.contains(&right_down) {
-                sand = right_down;
-                continue;
+        }
+
+        unreachable!("the path always holds the grain's position")
+    }
+
+    // Drops grains until one falls into the abyss or the source is blocked,
+    // returning how many have settled.
+    pub fn fill(&mut self) -> usize {
+        while let Grain::Settled(_) = self.drop_grain() {}
+        self.settled
+    }
+
+    // With a floor, sand ends up on every square it can reach: those below
+    // the source, or below and beside another grain, that aren't rock. This
+    // finds them row by row, returning how many have settled, without
+    // simulating a single grain.
+    pub fn flood_fill(&mut self) -> Option<usize> {
+        let floor = self.floor?;
+        let (source_x, source_y) = self.source;
+        if self.get(self.source) == Some(Tile::Rock) {
+            return Some(self.settled);
+        }
+
+        let width = self.tiles.width();
+        let mut reached = vec![false; width];
+        reached[source_x - self.left] = true;
+        self.tiles[(source_x - self.left, source_y)] = Tile::Sand;
+        let mut settled = 1;
+
+        for y in source_y + 1..floor {
+            let above = std::mem::replace(&mut reached, vec![false; width]);
+            for x in 0..width {
+                let from_above = above[x.saturating_sub(1)..(x + 2).min(width)]
+                    .iter()
+                    .any(|&reached| reached);
+                if from_above && self.tiles[(x, y)] != Tile::Rock {
+                    reached[x] = true;
+                    self.tiles[(x, y)] = Tile::Sand;
+                    settled += 1;
+                }
             }
+        }
+
+        self.path.clear();
+        self.settled = settled;
+        Some(settled)
+    }
+}
 
-            if sand == sand_start {
-                return count + 1;
+#[derive(Debug, Clone)]
+pub struct Frames {
+    cave: Cave,
+    every: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl Frames {
+    pub fn cave(&self) -> &Cave {
+        &self.cave
+    }
+}
+
+impl Iterator for Frames {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            return Some(self.cave.render());
+        }
+        if self.finished {
+            return None;
+        }
+
+        let before = self.cave.settled();
+        while self.cave.settled() - before < self.every {
+            if !matches!(self.cave.drop_grain(), Grain::Settled(_)) {
+                self.finished = true;
+                break;
             }
+        }
+        (self.cave.settled() > before).then(|| self.cave.render())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    SourceBelowFloor {
+        source: (usize, usize),
+        floor: usize,
+    },
+    SpreadsPastEdge {
+        source: (usize, usize),
+    },
+}
 
-            filled.insert(sand);
-            sand = sand_start;
-            count += 1;
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SourceBelowFloor { source, floor } => {
+                write!(f, "source {source:?} is below the floor at y = {floor}")
+            }
+            Error::SpreadsPastEdge { source } => {
+                write!(f, "sand poured from {source:?} could spread past x = 0")
+            }
         }
     }
 }
 
+impl std::error::Error for Error {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,34 +446,413 @@ mod tests {
         }
     }
 
+    mod drop_grain {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            config: Config,
+            expected: Vec<Grain>,
+        }
+
+        #[test]
+        fn example() {
+            run(&Case {
+                data: super::example().1,
+                config: Config::default(),
+                expected: vec![
+                    Grain::Settled((500, 8)),
+                    Grain::Settled((499, 8)),
+                    Grain::Settled((501, 8)),
+                    Grain::Settled((500, 7)),
+                    Grain::Settled((498, 8)),
+                ],
+            })
+        }
+
+        #[test]
+        fn blocked_source() {
+            run(&Case {
+                data: super::example().1,
+                config: Config {
+                    source: (498, 4),
+                    floor: Some(2),
+                },
+                expected: vec![Grain::Blocked, Grain::Blocked],
+            })
+        }
+
+        #[test]
+        fn beside_the_rocks() {
+            run(&Case {
+                data: super::example().1,
+                config: Config {
+                    source: (490, 0),
+                    ..Config::default()
+                },
+                expected: vec![Grain::Abyss, Grain::Abyss],
+            })
+        }
+
+        #[test]
+        fn below_the_rocks() {
+            run(&Case {
+                data: super::example().1,
+                config: Config {
+                    source: (500, 20),
+                    ..Config::default()
+                },
+                expected: vec![Grain::Abyss, Grain::Abyss],
+            })
+        }
+
+        #[test]
+        fn off_the_left_edge() {
+            run(&Case {
+                data: Input::from_data("0,3 -> 1,3"),
+                config: Config {
+                    source: (0, 0),
+                    ..Config::default()
+                },
+                expected: vec![Grain::Abyss, Grain::Abyss],
+            })
+        }
+
+        fn run(test: &Case) {
+            let mut cave = test.data.cave(test.config).unwrap();
+            let grains = test
+                .expected
+                .iter()
+                .map(|_| cave.drop_grain())
+                .collect::<Vec<_>>();
+            assert_eq!(test.expected, grains)
+        }
+    }
+
+    mod fill {
+        use super::*;
+
+        struct Case {
+            config: Config,
+            expected: usize,
+        }
+
+        #[test]
+        fn abyss() {
+            run(&Case {
+                config: Config::default(),
+                expected: 24,
+            })
+        }
+
+        #[test]
+        fn floor() {
+            run(&Case {
+                config: Config {
+                    floor: Some(2),
+                    ..Config::default()
+                },
+                expected: 93,
+            })
+        }
+
+        #[test]
+        fn lower_floor() {
+            run(&Case {
+                config: Config {
+                    floor: Some(3),
+                    ..Config::default()
+                },
+                expected: 111,
+            })
+        }
+
+        #[test]
+        fn moved_source() {
+            run(&Case {
+                config: Config {
+                    source: (497, 2),
+                    floor: Some(2),
+                },
+                expected: 50,
+            })
+        }
+
+        fn run(test: &Case) {
+            let mut cave = super::example().1.cave(test.config).unwrap();
+            assert_eq!(test.expected, cave.fill())
+        }
+    }
+
+    mod flood_fill {
+        use super::*;
+
+        struct Case {
+            data: Input,
+            config: Config,
+            expected: Result<usize, Error>,
+        }
+
+        #[test]
+        fn floor() {
+            run(&Case {
+                data: super::example().1,
+                config: Config {
+                    floor: Some(2),
+                    ..Config::default()
+                },
+                expected: Ok(93),
+            })
+        }
+
+        #[test]
+        fn lower_floor() {
+            run(&Case {
+                data: super::example().1,
+                config: Config {
+                    floor: Some(3),
+                    ..Config::default()
+                },
+                expected: Ok(111),
+            })
+        }
+
+        #[test]
+        fn moved_source() {
+            run(&Case {
+                data: super::example().1,
+                config: Config {
+                    source: (497, 2),
+                    floor: Some(2),
+                },
+                expected: Ok(50),
+            })
+        }
+
+        #[test]
+        fn at_left_edge() {
+            run(&Case {
+                data: Input::from_data("5,3 -> 6,3"),
+                config: Config {
+                    source: (4, 0),
+                    floor: Some(2),
+                },
+                expected: Ok(23),
+            })
+        }
+
+        #[test]
+        fn past_left_edge() {
+            run(&Case {
+                data: Input::from_data("5,3 -> 6,3"),
+                config: Config {
+                    source: (2, 0),
+                    floor: Some(2),
+                },
+                expected: Err(Error::SpreadsPastEdge { source: (2, 0) }),
+            })
+        }
+
+        #[test]
+        fn below_floor() {
+            run(&Case {
+                data: super::example().1,
+                config: Config {
+                    source: (500, 20),
+                    floor: Some(2),
+                },
+                expected: Err(Error::SourceBelowFloor {
+                    source: (500, 20),
+                    floor: 11,
+                }),
+            })
+        }
+
+        #[test]
+        fn no_floor() {
+            let mut cave = super::example().1.cave(Config::default()).unwrap();
+            assert_eq!(None, cave.flood_fill())
+        }
+
+        fn run(test: &Case) {
+            let simulated = test.data.cave(test.config).map(|mut cave| {
+                let settled = cave.fill();
+                (settled, cave)
+            });
+            let flooded = test.data.cave(test.config).map(|mut cave| {
+                let settled = cave.flood_fill().unwrap();
+                (settled, cave)
+            });
+            assert_eq!(
+                test.expected,
+                simulated
+                    .as_ref()
+                    .map(|&(settled, _)| settled)
+                    .map_err(Clone::clone)
+            );
+            assert_eq!(simulated, flooded)
+        }
+    }
+
+    mod render {
+        use super::*;
+
+        struct Case {
+            config: Config,
+            fill: bool,
+            expected: &'static str,
+        }
+
+        #[test]
+        fn empty() {
+            run(&Case {
+                config: Config::default(),
+                fill: false,
+                expected: "......+...
+..........
+..........
+..........
+....#...##
+....#...#.
+..###...#.
+........#.
+........#.
+#########.",
+            })
+        }
+
+        #[test]
+        fn abyss() {
+            run(&Case {
+                config: Config::default(),
+                fill: true,
+                expected: "......+...
+..........
+......o...
+.....ooo..
+....#ooo##
+...o#ooo#.
+..###ooo#.
+....oooo#.
+.o.ooooo#.
+#########.",
+            })
+        }
+
+        #[test]
+        fn floor() {
+            run(&Case {
+                config: Config {
+                    floor: Some(2),
+                    ..Config::default()
+                },
+                fill: true,
+                expected: "..........o..........
+.........ooo.........
+........ooooo........
+.......ooooooo.......
+......oo#ooo##o......
+.....ooo#ooo#ooo.....
+....oo###ooo#oooo....
+...oooo.oooo#ooooo...
+..oooooooooo#oooooo..
+.ooo#########ooooooo.
+ooooo.......ooooooooo
+#####################",
+            })
+        }
+
+        fn run(test: &Case) {
+            let mut cave = super::example().1.cave(test.config).unwrap();
+            if test.fill {
+                cave.fill();
+            }
+            assert_eq!(test.expected, cave.render())
+        }
+    }
+
+    mod frames {
+        use super::*;
+
+        struct Case {
+            every: NonZeroUsize,
+            expected: usize,
+        }
+
+        #[test]
+        fn every_grain() {
+            run(&Case {
+                every: NonZeroUsize::new(1).unwrap(),
+                expected: 25,
+            })
+        }
+
+        #[test]
+        fn every_ten_grains() {
+            run(&Case {
+                every: NonZeroUsize::new(10).unwrap(),
+                expected: 4,
+            })
+        }
+
+        #[test]
+        fn every_grain_at_once() {
+            run(&Case {
+                every: NonZeroUsize::new(100).unwrap(),
+                expected: 2,
+            })
+        }
+
+        fn run(test: &Case) {
+            let input = super::example().1;
+            let mut filled = input.cave(Config::default()).unwrap();
+            filled.fill();
+
+            let frames = input
+                .cave(Config::default())
+                .unwrap()
+                .frames(test.every)
+                .collect::<Vec<_>>();
+            assert_eq!(test.expected, frames.len());
+            assert_eq!(
+                Some(&input.cave(Config::default()).unwrap().render()),
+                frames.first()
+            );
+            assert_eq!(Some(&filled.render()), frames.last())
+        }
+    }
+
     fn example() -> (&'static str, Input) {
         (
             "498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9",
             Input {
                 max_y: 9,
-                rocks: HashSet::from([
-                    (498, 4),
-                    (498, 5),
-                    (498, 6),
-                    (497, 6),
-                    (496, 6),
-                    (503, 4),
-                    (502, 4),
-                    (502, 5),
-                    (502, 6),
-                    (502, 7),
-                    (502, 8),
-                    (502, 9),
-                    (501, 9),
-                    (500, 9),
-                    (499, 9),
-                    (498, 9),
-                    (497, 9),
-                    (496, 9),
-                    (495, 9),
-                    (494, 9),
-                ]),
+                cave: SparseGrid::from_iter(
+                    [
+                        (498, 4),
+                        (498, 5),
+                        (498, 6),
+                        (497, 6),
+                        (496, 6),
+                        (503, 4),
+                        (502, 4),
+                        (502, 5),
+                        (502, 6),
+                        (502, 7),
+                        (502, 8),
+                        (502, 9),
+                        (501, 9),
+                        (500, 9),
+                        (499, 9),
+                        (498, 9),
+                        (497, 9),
+                        (496, 9),
+                        (495, 9),
+                        (494, 9),
+                    ]
+                    .map(|point| (point, Tile::Rock)),
+                ),
             },
         )
     }
@@ -0,0 +1,488 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    ops::{Index, IndexMut},
+};
+
+// Points are `(x, y)`, with `y` growing downwards as in the puzzle diagrams.
+pub type Point = (usize, usize);
+
+// Cells are stored row by row.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    // A grid with no columns has no rows either, as with `from_cells`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let height = if width == 0 { 0 } else { height };
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Option<Self> {
+        if width == 0 {
+            return cells.is_empty().then_some(Self {
+                width,
+                height: 0,
+                cells,
+            });
+        }
+        cells.len().is_multiple_of(width).then(|| Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        })
+    }
+
+    pub fn parse(text: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut cells = Vec::with_capacity(text.len());
+        for (y, line) in text.lines().enumerate() {
+            let len = cells.len();
+            for (x, char) in line.chars().enumerate() {
+                cells.push(cell(char).ok_or(ParseError::InvalidCell {
+                    point: (x, y),
+                    char,
+                })?);
+            }
+
+            let row_width = cells.len() - len;
+            match width {
+                None => width = Some(row_width),
+                Some(expected) if expected != row_width => {
+                    return Err(ParseError::RaggedRow {
+                        row: y,
+                        width: row_width,
+                        expected,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self::from_cells(width.unwrap_or_default(), cells).unwrap())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|idx| &mut self.cells[idx])
+    }
+
+    // The orthogonal neighbours that lie within the grid.
+    pub fn neighbours(&self, (x, y): Point) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width, self.height);
+        [
+            y.checked_sub(1).map(|y| (x, y)),
+            x.checked_sub(1).map(|x| (x, y)),
+            x.checked_add(1).map(|x| (x, y)),
+            y.checked_add(1).map(|y| (x, y)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(move |&(x, y)| x < width && y < height)
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        // `chunks` panics on zero, but a zero-width grid has no cells anyway.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        let cells = if x < self.width {
+            &self.cells[x..]
+        } else {
+            &[]
+        };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn points(&self) -> impl DoubleEndedIterator<Item = Point> + ExactSizeIterator {
+        let width = self.width.max(1);
+        (0..self.cells.len()).map(move |idx| (idx % width, idx / width))
+    }
+
+    pub fn cells(&self) -> impl DoubleEndedIterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|idx| (idx % self.width, idx / self.width))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn render(&self, mut cell: impl FnMut(Point, &T) -> char) -> String {
+        let mut output = String::with_capacity(self.cells.len() + self.height);
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                output.push('\n');
+            }
+            for (x, value) in row.iter().enumerate() {
+                output.push(cell((x, idx), value));
+            }
+        }
+        output
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point).then(|| point.1 * self.width + point.0)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point).unwrap()
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point).unwrap()
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                f.write_char('\n')?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Unbounded, so points may be negative. Only occupied cells take up space.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(i64, i64), T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, point: (i64, i64), value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: (i64, i64)) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    pub fn get(&self, point: (i64, i64)) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn contains(&self, point: (i64, i64)) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.cells.iter().map(|(&point, value)| (point, value))
+    }
+
+    // The occupied orthogonal neighbours.
+    pub fn neighbours(&self, (x, y): (i64, i64)) -> impl Iterator<Item = ((i64, i64), &T)> {
+        [(x, y - 1), (x - 1, y), (x + 1, y), (x, y + 1)]
+            .into_iter()
+            .filter_map(|point| self.get(point).map(|value| (point, value)))
+    }
+
+    // The smallest and largest corners enclosing every occupied cell.
+    pub fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        self.cells.keys().fold(None, |bounds, &(x, y)| {
+            let ((min_x, min_y), (max_x, max_y)) = bounds.unwrap_or(((x, y), (x, y)));
+            Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+        })
+    }
+
+    // Renders the bounding box of the occupied cells.
+    pub fn render(&self, mut cell: impl FnMut((i64, i64), Option<&T>) -> char) -> String {
+        let mut output = String::new();
+        if let Some(((min_x, min_y), (max_x, max_y))) = self.bounds() {
+            for y in min_y..=max_y {
+                if y > min_y {
+                    output.push('\n');
+                }
+                for x in min_x..=max_x {
+                    output.push(cell((x, y), self.get((x, y))));
+                }
+            }
+        }
+        output
+    }
+}
+
+impl<T> FromIterator<((i64, i64), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((i64, i64), T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidCell {
+        point: Point,
+        char: char,
+    },
+    RaggedRow {
+        row: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidCell {
+                point: (x, y),
+                char,
+            } => write!(f, "unexpected {char:?} at ({x}, {y})"),
+            ParseError::RaggedRow {
+                row,
+                width,
+                expected,
+            } => write!(f, "row {row} is {width} wide, expected {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(text: &str) -> Grid<u8> {
+        Grid::parse(text, |char| char.to_digit(10).map(|digit| digit as u8)).unwrap()
+    }
+
+    mod parse {
+        use super::*;
+
+        struct Case {
+            input: &'static str,
+            expected: Result<Grid<u8>, ParseError>,
+        }
+
+        #[test]
+        fn rectangle() {
+            run(&Case {
+                input: "123\n456",
+                expected: Ok(Grid::from_cells(3, vec![1, 2, 3, 4, 5, 6]).unwrap()),
+            })
+        }
+
+        #[test]
+        fn empty() {
+            run(&Case {
+                input: "",
+                expected: Ok(Grid::default()),
+            })
+        }
+
+        #[test]
+        fn invalid_cell() {
+            run(&Case {
+                input: "123\n4x6",
+                expected: Err(ParseError::InvalidCell {
+                    point: (1, 1),
+                    char: 'x',
+                }),
+            })
+        }
+
+        #[test]
+        fn ragged_row() {
+            run(&Case {
+                input: "123\n45",
+                expected: Err(ParseError::RaggedRow {
+                    row: 1,
+                    width: 2,
+                    expected: 3,
+                }),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                Grid::parse(test.input, |char| char
+                    .to_digit(10)
+                    .map(|digit| digit as u8))
+            )
+        }
+    }
+
+    mod grid {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let text = "123\n456\n789";
+            assert_eq!(text, digits(text).to_string());
+        }
+
+        #[test]
+        fn neighbours() {
+            let grid = digits("123\n456\n789");
+            assert_eq!(
+                vec![(1, 0), (0, 1), (2, 1), (1, 2)],
+                grid.neighbours((1, 1)).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![(1, 0), (0, 1)],
+                grid.neighbours((0, 0)).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![(2, 1), (1, 2)],
+                grid.neighbours((2, 2)).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn get_outside() {
+            let grid = digits("123\n456");
+            assert_eq!(Some(&6), grid.get((2, 1)));
+            assert_eq!(None, grid.get((usize::MAX, 0)));
+            assert_eq!(None, grid.get((0, usize::MAX)));
+        }
+
+        #[test]
+        fn new_without_columns() {
+            let grid = Grid::new(0, 3, 0u8);
+            assert_eq!((0, 0), (grid.width(), grid.height()));
+            assert_eq!(grid.height(), grid.rows().count());
+            assert_eq!(Grid::from_cells(0, Vec::new()), Some(grid));
+        }
+
+        #[test]
+        fn rows_and_columns() {
+            let grid = digits("123\n456");
+            assert_eq!(Some(&[4, 5, 6][..]), grid.row(1));
+            assert_eq!(None, grid.row(2));
+            assert_eq!(
+                vec![vec![1, 4], vec![2, 5], vec![3, 6]],
+                grid.columns()
+                    .map(|column| column.copied().collect::<Vec<_>>())
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(0, grid.column(3).count());
+        }
+
+        #[test]
+        fn indexing() {
+            let mut grid = digits("123\n456");
+            grid[(2, 0)] = 9;
+            assert_eq!(Some(&9), grid.get((2, 0)));
+            assert_eq!(None, grid.get((3, 0)));
+            assert_eq!(None, grid.get((0, 2)));
+            assert_eq!(Some((1, 1)), grid.position(|&cell| cell == 5));
+        }
+
+        #[test]
+        fn render() {
+            let grid = digits("19\n91");
+            assert_eq!(
+                "#.\n.#",
+                grid.render(|_, &cell| if cell == 1 { '#' } else { '.' })
+            );
+        }
+    }
+
+    mod sparse_grid {
+        use super::*;
+
+        #[test]
+        fn render() {
+            let grid = [((-1, 2), '#'), ((1, 0), 'o')]
+                .into_iter()
+                .collect::<SparseGrid<_>>();
+            assert_eq!(Some(((-1, 0), (1, 2))), grid.bounds());
+            assert_eq!(
+                "..o\n...\n#..",
+                grid.render(|_, cell| cell.copied().unwrap_or('.'))
+            );
+        }
+
+        #[test]
+        fn neighbours() {
+            let grid = [((0, 0), 1), ((0, 1), 2), ((5, 5), 3)]
+                .into_iter()
+                .collect::<SparseGrid<_>>();
+            assert_eq!(
+                vec![((0, 1), &2)],
+                grid.neighbours((0, 0)).collect::<Vec<_>>()
+            );
+            assert!(SparseGrid::<u8>::new().bounds().is_none());
+        }
+    }
+}
@@ -0,0 +1,422 @@
+use std::{collections::BTreeMap, fmt::Display, num::NonZeroUsize};
+
+pub trait Point: Copy + Ord {
+    const MAX: Self;
+
+    fn successor(self) -> Option<Self>;
+    fn predecessor(self) -> Option<Self>;
+    fn distance(self, other: Self) -> u64;
+}
+
+macro_rules! impl_point {
+    ($($ty:ty),*) => {
+        $(
+            impl Point for $ty {
+                const MAX: Self = <$ty>::MAX;
+
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn distance(self, other: Self) -> u64 {
+                    self.abs_diff(other) as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_point!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+// Both ends of an interval are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Point> Interval<T> {
+    pub fn new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    pub fn point(point: T) -> Self {
+        Self {
+            start: point,
+            end: point,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn size(&self) -> u64 {
+        self.start.distance(self.end).saturating_add(1)
+    }
+
+    pub fn contains(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn contains_point(&self, point: T) -> bool {
+        self.start <= point && point <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // Overlapping or adjacent intervals can be merged into one.
+    fn touches(&self, other: &Self) -> bool {
+        self.overlaps(other)
+            || self.end.successor() == Some(other.start)
+            || other.end.successor() == Some(self.start)
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// Intervals are kept sorted, disjoint and non-adjacent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Point> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let first = self.intervals.partition_point(|existing| {
+            existing.end < interval.start && !existing.touches(&interval)
+        });
+        let last = first
+            + self.intervals[first..]
+                .iter()
+                .take_while(|existing| existing.touches(&interval))
+                .count();
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |acc, existing| Interval {
+                start: acc.start.min(existing.start),
+                end: acc.end.max(existing.end),
+            });
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut output = self.clone();
+        for &interval in &other.intervals {
+            output.insert(interval);
+        }
+        output
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+
+        let (mut lhs, mut rhs) = (self.intervals.iter(), other.intervals.iter());
+        let (mut left, mut right) = (lhs.next(), rhs.next());
+        while let (Some(l), Some(r)) = (left, right) {
+            if let Some(shared) = l.intersection(r) {
+                intervals.push(shared);
+            }
+            if l.end < r.end {
+                left = lhs.next();
+            } else {
+                right = rhs.next();
+            }
+        }
+
+        Self { intervals }
+    }
+
+    pub fn contains(&self, interval: &Interval<T>) -> bool {
+        let idx = self
+            .intervals
+            .partition_point(|existing| existing.end < interval.start);
+        self.intervals
+            .get(idx)
+            .is_some_and(|existing| existing.contains(interval))
+    }
+
+    pub fn contains_point(&self, point: T) -> bool {
+        self.contains(&Interval::point(point))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn size(&self) -> u64 {
+        self.intervals.iter().map(Interval::size).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+}
+
+impl<T: Point> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut output = Self::new();
+        for interval in iter {
+            output.insert(interval);
+        }
+        output
+    }
+}
+
+// Returns the points covered by at least `depth` of the given intervals. A
+// depth of zero would cover every point, intervals or not, so isn't allowed.
+pub fn covered_at_least<T: Point>(
+    intervals: impl IntoIterator<Item = Interval<T>>,
+    depth: NonZeroUsize,
+) -> IntervalSet<T> {
+    let mut deltas = BTreeMap::<T, isize>::new();
+    for interval in intervals {
+        *deltas.entry(interval.start).or_default() += 1;
+        if let Some(after) = interval.end.successor() {
+            *deltas.entry(after).or_default() -= 1;
+        }
+    }
+
+    let mut output = IntervalSet::new();
+    let mut current = 0;
+    let mut covered_from = None;
+    for (point, delta) in deltas {
+        current += delta;
+        match (covered_from, current >= depth.get() as isize) {
+            (None, true) => covered_from = Some(point),
+            (Some(start), false) => {
+                output.insert(Interval {
+                    start,
+                    end: point.predecessor().unwrap(),
+                });
+                covered_from = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = covered_from {
+        output.insert(Interval { start, end: T::MAX });
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: u32, end: u32) -> Interval<u32> {
+        Interval::new(start, end).unwrap()
+    }
+
+    fn set(intervals: &[(u32, u32)]) -> IntervalSet<u32> {
+        intervals
+            .iter()
+            .map(|&(start, end)| interval(start, end))
+            .collect()
+    }
+
+    mod interval {
+        use super::*;
+
+        #[test]
+        fn new_rejects_reversed_bounds() {
+            assert_eq!(None, Interval::new(5, 4));
+        }
+
+        #[test]
+        fn size() {
+            assert_eq!(1, interval(3, 3).size());
+            assert_eq!(6, Interval::new(-2i8, 3).unwrap().size());
+            assert_eq!(256, Interval::new(0u8, 255).unwrap().size());
+        }
+
+        #[test]
+        fn contains() {
+            assert!(interval(2, 8).contains(&interval(3, 7)));
+            assert!(interval(2, 8).contains(&interval(2, 8)));
+            assert!(!interval(3, 7).contains(&interval(2, 8)));
+        }
+
+        #[test]
+        fn overlaps() {
+            assert!(interval(5, 7).overlaps(&interval(7, 9)));
+            assert!(!interval(2, 4).overlaps(&interval(6, 8)));
+        }
+
+        #[test]
+        fn intersection() {
+            assert_eq!(
+                Some(interval(4, 6)),
+                interval(2, 6).intersection(&interval(4, 8))
+            );
+            assert_eq!(None, interval(2, 3).intersection(&interval(4, 5)));
+        }
+    }
+
+    mod interval_set {
+        use super::*;
+
+        struct Case {
+            lhs: IntervalSet<u32>,
+            rhs: IntervalSet<u32>,
+            union: IntervalSet<u32>,
+            intersection: IntervalSet<u32>,
+        }
+
+        #[test]
+        fn disjoint() {
+            run(&Case {
+                lhs: set(&[(1, 2)]),
+                rhs: set(&[(5, 6)]),
+                union: set(&[(1, 2), (5, 6)]),
+                intersection: set(&[]),
+            })
+        }
+
+        #[test]
+        fn adjacent() {
+            run(&Case {
+                lhs: set(&[(1, 2), (7, 8)]),
+                rhs: set(&[(3, 6)]),
+                union: set(&[(1, 8)]),
+                intersection: set(&[]),
+            })
+        }
+
+        #[test]
+        fn overlapping() {
+            run(&Case {
+                lhs: set(&[(1, 5), (10, 20)]),
+                rhs: set(&[(4, 12), (18, 25)]),
+                union: set(&[(1, 25)]),
+                intersection: set(&[(4, 5), (10, 12), (18, 20)]),
+            })
+        }
+
+        #[test]
+        fn nested() {
+            run(&Case {
+                lhs: set(&[(0, 100)]),
+                rhs: set(&[(10, 20), (30, 40)]),
+                union: set(&[(0, 100)]),
+                intersection: set(&[(10, 20), (30, 40)]),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(test.union, test.lhs.union(&test.rhs));
+            assert_eq!(test.union, test.rhs.union(&test.lhs));
+            assert_eq!(test.intersection, test.lhs.intersection(&test.rhs));
+            assert_eq!(test.intersection, test.rhs.intersection(&test.lhs));
+        }
+
+        #[test]
+        fn insert_merges_in_order() {
+            let mut intervals = IntervalSet::new();
+            for (start, end) in [(10, 12), (1, 2), (5, 6), (3, 4), (14, 15)] {
+                intervals.insert(interval(start, end));
+            }
+            assert_eq!(
+                vec![&interval(1, 6), &interval(10, 12), &interval(14, 15)],
+                intervals.iter().collect::<Vec<_>>()
+            );
+            assert_eq!(11, intervals.size());
+        }
+
+        #[test]
+        fn contains() {
+            let intervals = set(&[(1, 5), (10, 20)]);
+            assert!(intervals.contains(&interval(11, 19)));
+            assert!(intervals.contains_point(5));
+            assert!(!intervals.contains(&interval(4, 10)));
+            assert!(!intervals.contains_point(7));
+            assert!(!IntervalSet::new().contains_point(7));
+        }
+    }
+
+    mod covered_at_least {
+        use super::*;
+
+        struct Case {
+            intervals: Vec<Interval<u32>>,
+            depth: NonZeroUsize,
+            expected: IntervalSet<u32>,
+        }
+
+        #[test]
+        fn once() {
+            run(&Case {
+                intervals: vec![interval(1, 3), interval(6, 8), interval(2, 4)],
+                depth: NonZeroUsize::MIN,
+                expected: set(&[(1, 4), (6, 8)]),
+            })
+        }
+
+        #[test]
+        fn twice() {
+            run(&Case {
+                intervals: vec![interval(1, 5), interval(4, 8), interval(8, 9)],
+                depth: NonZeroUsize::new(2).unwrap(),
+                expected: set(&[(4, 5), (8, 8)]),
+            })
+        }
+
+        #[test]
+        fn thrice() {
+            run(&Case {
+                intervals: vec![interval(1, 5), interval(4, 8), interval(8, 9)],
+                depth: NonZeroUsize::new(3).unwrap(),
+                expected: set(&[]),
+            })
+        }
+
+        #[test]
+        fn up_to_max() {
+            run(&Case {
+                intervals: vec![interval(1, u32::MAX), interval(10, u32::MAX)],
+                depth: NonZeroUsize::new(2).unwrap(),
+                expected: set(&[(10, u32::MAX)]),
+            })
+        }
+
+        fn run(test: &Case) {
+            assert_eq!(
+                test.expected,
+                covered_at_least(test.intervals.iter().copied(), test.depth)
+            )
+        }
+    }
+}
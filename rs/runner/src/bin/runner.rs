@@ -0,0 +1,60 @@
+use std::{num::NonZeroUsize, thread, time::Instant};
+
+use clap::Parser;
+use runner::{run_ordered, Day, DAYS};
+
+#[derive(clap::Parser, Debug)]
+struct Opts {
+    #[arg(
+        short,
+        long,
+        use_value_delimiter = true,
+        value_parser = clap::value_parser!(u32).range(1..=DAYS.len() as i64),
+        help = "Days to run (defaults to every solved day)"
+    )]
+    days: Vec<u32>,
+
+    #[arg(
+        short,
+        long,
+        help = "Number of days to run concurrently (defaults to the available parallelism)"
+    )]
+    jobs: Option<NonZeroUsize>,
+
+    #[arg(short, long, help = "Run part 1 and part 2 of each day concurrently")]
+    parallel_parts: bool,
+
+    #[arg(
+        long,
+        default_value = "./data",
+        help = "Directory containing the input data"
+    )]
+    data_dir: String,
+}
+
+fn main() {
+    let opts = Opts::parse();
+
+    let days = if opts.days.is_empty() {
+        DAYS.to_vec()
+    } else {
+        opts.days
+            .iter()
+            .map(|&day| DAYS[day as usize - 1])
+            .collect::<Vec<Day>>()
+    };
+
+    let jobs = opts
+        .jobs
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+
+    let tasks = days
+        .iter()
+        .map(|day| || (day.run)(&opts.data_dir, opts.parallel_parts))
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    run_ordered(&tasks, jobs, |report| println!("{report}\n"));
+    println!("Ran {} day(s) in {:?}", days.len(), start.elapsed());
+}
@@ -0,0 +1,275 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+macro_rules! day {
+    ($number:literal, $krate:ident $(, $parsed:ident)?) => {
+        Day {
+            number: $number,
+            run: |data_dir, parallel_parts| {
+                let data = $krate::read_data(data_dir);
+                solve(
+                    $number,
+                    || $krate::Input::from_data(&data)$(.$parsed())?,
+                    |input| input.part_1(),
+                    |input| input.part_2(),
+                    parallel_parts,
+                )
+            },
+        }
+    };
+}
+
+pub const DAYS: [Day; 15] = [
+    day!(1, day_01),
+    day!(2, day_02),
+    day!(3, day_03, unwrap),
+    day!(4, day_04),
+    day!(5, day_05, unwrap),
+    day!(6, day_06),
+    day!(7, day_07, unwrap),
+    day!(8, day_08),
+    day!(9, day_09, unwrap),
+    day!(10, day_10, unwrap),
+    day!(11, day_11, unwrap),
+    day!(12, day_12),
+    day!(13, day_13, unwrap),
+    day!(14, day_14),
+    day!(15, day_15),
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub number: u32,
+    pub run: fn(data_dir: &str, parallel_parts: bool) -> Report,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timed<T> {
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+impl<T> Timed<T> {
+    fn run(f: impl FnOnce() -> T) -> Self {
+        let start = Instant::now();
+        let value = f();
+        Self {
+            value,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub day: u32,
+    pub parse: Duration,
+    pub part_1: Timed<String>,
+    pub part_2: Timed<String>,
+    pub total: Duration,
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Day {:02} (parse {:?}, total {:?})",
+            self.day, self.parse, self.total
+        )?;
+        writeln!(
+            f,
+            "  Part 1 ({:?}): {}",
+            self.part_1.elapsed, self.part_1.value
+        )?;
+        write!(
+            f,
+            "  Part 2 ({:?}): {}",
+            self.part_2.elapsed, self.part_2.value
+        )
+    }
+}
+
+pub fn solve<I, P1, P2>(
+    day: u32,
+    parse: impl FnOnce() -> I,
+    part_1: impl FnOnce(&I) -> P1 + Send,
+    part_2: impl FnOnce(&I) -> P2 + Send,
+    parallel_parts: bool,
+) -> Report
+where
+    I: Sync,
+    P1: Display,
+    P2: Display,
+{
+    let start = Instant::now();
+
+    let input = Timed::run(parse);
+    let input_ref = &input.value;
+
+    // Answers are formatted on the thread that produced them, so the part
+    // outputs themselves never need to be `Send`.
+    let part_1 = move || Timed::run(|| part_1(input_ref).to_string());
+    let part_2 = move || Timed::run(|| part_2(input_ref).to_string());
+
+    let (part_1, part_2) = if parallel_parts {
+        thread::scope(|scope| {
+            let part_2 = scope.spawn(part_2);
+            let part_1 = part_1();
+            (part_1, part_2.join().unwrap())
+        })
+    } else {
+        (part_1(), part_2())
+    };
+
+    Report {
+        day,
+        parse: input.elapsed,
+        part_1,
+        part_2,
+        total: start.elapsed(),
+    }
+}
+
+pub fn run_ordered<T, F>(tasks: &[F], workers: usize, mut emit: impl FnMut(T))
+where
+    T: Send,
+    F: Fn() -> T + Sync,
+{
+    let next_task = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, tasks.len().max(1)) {
+            let sender = sender.clone();
+            let next_task = &next_task;
+            scope.spawn(move || loop {
+                let idx = next_task.fetch_add(1, Ordering::Relaxed);
+                let task = match tasks.get(idx) {
+                    Some(task) => task,
+                    None => break,
+                };
+                if sender.send((idx, task())).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut next_emit = 0;
+        for (idx, result) in receiver {
+            pending.insert(idx, result);
+            while let Some(result) = pending.remove(&next_emit) {
+                emit(result);
+                next_emit += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod solve {
+        use super::*;
+
+        struct Case {
+            parallel_parts: bool,
+            expected: [&'static str; 2],
+        }
+
+        #[test]
+        fn sequential() {
+            run(&Case {
+                parallel_parts: false,
+                expected: ["6", "abc"],
+            })
+        }
+
+        #[test]
+        fn parallel() {
+            run(&Case {
+                parallel_parts: true,
+                expected: ["6", "abc"],
+            })
+        }
+
+        fn run(test: &Case) {
+            let report = solve(
+                1,
+                || vec![1, 2, 3],
+                |input| input.iter().sum::<usize>(),
+                |input| {
+                    input
+                        .iter()
+                        .map(|&n| (b'a' + n as u8 - 1) as char)
+                        .collect::<String>()
+                },
+                test.parallel_parts,
+            );
+            assert_eq!(
+                test.expected,
+                [report.part_1.value.as_str(), report.part_2.value.as_str()]
+            )
+        }
+    }
+
+    mod run_ordered {
+        use super::*;
+
+        struct Case {
+            workers: usize,
+            task_count: usize,
+        }
+
+        #[test]
+        fn single_worker() {
+            run(&Case {
+                workers: 1,
+                task_count: 5,
+            })
+        }
+
+        #[test]
+        fn more_workers_than_tasks() {
+            run(&Case {
+                workers: 8,
+                task_count: 3,
+            })
+        }
+
+        #[test]
+        fn no_tasks() {
+            run(&Case {
+                workers: 4,
+                task_count: 0,
+            })
+        }
+
+        fn run(test: &Case) {
+            // Earlier tasks sleep for longer, so they finish out of order.
+            let tasks = (0..test.task_count)
+                .map(|idx| {
+                    move || {
+                        thread::sleep(Duration::from_millis(5 * (test.task_count - idx) as u64));
+                        idx
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let mut emitted = Vec::new();
+            run_ordered(&tasks, test.workers, |idx| emitted.push(idx));
+
+            assert_eq!((0..test.task_count).collect::<Vec<_>>(), emitted)
+        }
+    }
+}